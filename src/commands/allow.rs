@@ -0,0 +1,66 @@
+use anyhow::{bail, Result};
+use colored::*;
+use std::path::{Path, PathBuf};
+
+use crate::config::{Config, ScriptSource};
+use crate::git::GitRepo;
+use crate::trust::TrustStore;
+
+/// Allows the current content of setup scripts to run during `setup`. `path`
+/// may be a script or a checkout containing the configured setup scripts, and
+/// defaults to the current directory.
+pub fn execute(path: Option<&Path>) -> Result<()> {
+    let path = match path {
+        Some(path) => path.to_path_buf(),
+        None => std::env::current_dir()?,
+    };
+    let scripts = if path.is_dir() {
+        configured_scripts(&path)
+    } else {
+        vec![path]
+    };
+
+    let store = TrustStore::open();
+    let mut allowed = 0;
+    for script in scripts.iter().filter(|script| script.is_file()) {
+        let hash = store.allow(script)?;
+        crate::outln!(
+            "{} Allowed {} (sha256 {})",
+            "✅".green(),
+            script.display(),
+            &hash[..12]
+        );
+        allowed += 1;
+    }
+
+    if allowed == 0 {
+        let names = scripts
+            .iter()
+            .map(|script| format!("  - {}", script.display()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        bail!("No setup script found. Looked for:\n{names}");
+    }
+    crate::outln!("   They will run during setup until their content changes");
+    Ok(())
+}
+
+/// The setup scripts `.workbloom` configures for the checkout at `dir`. When
+/// scripts are taken from the main checkout, that copy is the one allowed.
+fn configured_scripts(dir: &Path) -> Vec<PathBuf> {
+    let repo = GitRepo::discover(dir).ok();
+    let config = repo
+        .as_ref()
+        .and_then(|repo| Config::load_from_file(&repo.root_dir).ok())
+        .unwrap_or_default();
+    let source_dir = match (config.setup_script_source, &repo) {
+        (ScriptSource::Main, Some(repo)) => repo.root_dir.as_path(),
+        _ => dir,
+    };
+
+    config
+        .setup_scripts()
+        .into_iter()
+        .map(|name| source_dir.join(name))
+        .collect()
+}
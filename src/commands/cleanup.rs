@@ -1,19 +1,43 @@
-use anyhow::{Context, Result};
+use anyhow::Result;
 use colored::*;
 use std::io::{self, Write};
-use std::time::SystemTime;
-
-use crate::{git::GitRepo, multiplexer};
-
-pub fn execute(mode: CleanupMode) -> Result<()> {
-    let repo = GitRepo::new()?;
+use std::time::Duration;
+
+use crate::config::{worktree_dir_name, Config};
+use crate::git::{GitRepo, MergeStrategy, UnsavedWork, WorktreeInfo};
+use crate::hooks::{self, HookContext, HookStage};
+use crate::metadata::{self, WorktreeMetadata};
+use crate::multiplexer;
+
+pub fn execute(mode: CleanupMode, options: &CleanupOptions) -> Result<()> {
+    let mut repo = GitRepo::new()?;
+    let config = Config::load_from_file(&repo.root_dir).unwrap_or_else(|_| Config::default());
+    config.warn_invalid_settings();
+    repo.set_default_branch(
+        options
+            .default_branch
+            .clone()
+            .or_else(|| config.default_branch.clone()),
+    )?;
+    let options = &CleanupOptions {
+        config,
+        ..options.clone()
+    };
+
+    if options.dry_run {
+        crate::outln!("{} Dry run: nothing will be removed", "🔍".blue());
+        crate::outln!();
+    }
 
+    let interactive = matches!(mode, CleanupMode::Interactive);
     match mode {
-        CleanupMode::Merged { force } => cleanup_merged_only(&repo, force),
-        CleanupMode::Pattern(pattern) => cleanup_by_pattern(&repo, &pattern),
-        CleanupMode::Interactive => interactive_cleanup(&repo),
-        CleanupMode::Status => show_status(&repo),
+        CleanupMode::Merged { .. } => cleanup_merged_only(&repo, options)?,
+        CleanupMode::Pattern(pattern) => cleanup_by_pattern(&repo, &pattern, options)?,
+        CleanupMode::Interactive => interactive_cleanup(&repo, options)?,
+        CleanupMode::Status => return show_status(&repo),
     }
+
+    handle_prunable_worktrees(&repo, interactive, options)
 }
 
 pub enum CleanupMode {
@@ -23,91 +47,131 @@ pub enum CleanupMode {
     Status,
 }
 
-pub fn cleanup_merged_worktrees(repo: &GitRepo) -> Result<()> {
-    cleanup_merged_worktrees_with_exclude(repo, None)
+#[derive(Debug, Clone, Default)]
+pub struct CleanupOptions {
+    /// Overrides the configured or detected default branch.
+    pub default_branch: Option<String>,
+    /// Report what would be removed without changing anything.
+    pub dry_run: bool,
+    /// Remove worktrees even if they contain uncommitted, untracked, stashed or unpushed work.
+    pub discard_unsaved: bool,
+    /// Hours after creation during which a worktree is never removed.
+    /// Overrides the `protect_hours` setting; 0 disables the guard.
+    pub protect_hours: Option<u64>,
+    /// Prune entries for worktrees whose directories no longer exist.
+    pub prune: bool,
+    /// Branches whose worktrees merged-mode cleanup never removes, e.g. ones
+    /// that `setup` just created.
+    pub exclude_branches: Vec<String>,
+    /// `.workbloom` settings of the repository being cleaned. `execute` loads
+    /// them itself.
+    pub config: Config,
 }
 
-pub fn cleanup_merged_worktrees_with_force(
+const DEFAULT_PROTECT_HOURS: u64 = 24;
+
+pub fn cleanup_merged_worktrees_excluding(
     repo: &GitRepo,
-    exclude_branch: Option<&str>,
-    force: bool,
+    config: &Config,
+    exclude: &[&str],
 ) -> Result<()> {
+    let options = CleanupOptions {
+        config: config.clone(),
+        ..CleanupOptions::default()
+    };
+    run_merged_cleanup(repo, exclude, &options)
+}
+
+fn run_merged_cleanup(repo: &GitRepo, exclude: &[&str], options: &CleanupOptions) -> Result<()> {
     crate::outln!(
         "{} Cleaning up worktrees for merged branches...",
         "🧹".yellow()
     );
 
-    let merged_branches = get_filtered_merged_branches(repo, exclude_branch, force)?;
+    let merged_branches = get_filtered_merged_branches(repo, exclude)?;
 
     if merged_branches.is_empty() {
         crate::outln!("{} No merged branches found", "✨".green());
         return Ok(());
     }
 
-    display_merged_branches(&merged_branches, exclude_branch);
+    display_merged_branches(&merged_branches, exclude);
 
-    let (cleaned_count, skipped_count) = process_worktrees(repo, &merged_branches)?;
+    let (cleaned_count, skipped_count) = process_worktrees(repo, &merged_branches, options)?;
 
-    display_cleanup_summary(cleaned_count, skipped_count);
+    display_cleanup_summary(cleaned_count, skipped_count, options.dry_run);
 
     Ok(())
 }
 
-pub fn cleanup_merged_worktrees_with_exclude(
-    repo: &GitRepo,
-    exclude_branch: Option<&str>,
-) -> Result<()> {
-    crate::outln!(
-        "{} Cleaning up worktrees for merged branches...",
-        "🧹".yellow()
-    );
-
-    let merged_branches = get_filtered_merged_branches(repo, exclude_branch, false)?;
-
-    if merged_branches.is_empty() {
-        crate::outln!("{} No merged branches found", "✨".green());
-        return Ok(());
-    }
-
-    display_merged_branches(&merged_branches, exclude_branch);
+/// A worktree branch that has landed in its merge target.
+struct MergedBranch {
+    name: String,
+    target: String,
+    strategy: MergeStrategy,
+}
 
-    let (cleaned_count, skipped_count) = process_worktrees(repo, &merged_branches)?;
+fn get_filtered_merged_branches(repo: &GitRepo, exclude: &[&str]) -> Result<Vec<MergedBranch>> {
+    crate::outln!("{} Getting list of merged branches...", "📋".blue());
+    let mut merged_branches = find_merged_worktree_branches(repo)?;
+    merged_branches.retain(|branch| !exclude.contains(&branch.name.as_str()));
 
-    display_cleanup_summary(cleaned_count, skipped_count);
+    // Apply safety filters to prevent deletion of new branches
+    merged_branches = apply_safety_filters(repo, merged_branches)?;
 
-    Ok(())
+    Ok(merged_branches)
 }
 
-fn get_filtered_merged_branches(
-    repo: &GitRepo,
-    exclude_branch: Option<&str>,
-    force: bool,
-) -> Result<Vec<String>> {
-    crate::outln!("{} Getting list of merged branches...", "📋".blue());
-    let mut merged_branches = repo.get_merged_branches()?;
+fn find_merged_worktree_branches(repo: &GitRepo) -> Result<Vec<MergedBranch>> {
+    let mut merged_branches = Vec::new();
 
-    if let Some(exclude) = exclude_branch {
-        merged_branches.retain(|branch| branch != exclude);
-    }
+    for worktree in repo.list_worktrees()? {
+        // Stale entries are handled by pruning instead
+        if worktree.path == repo.root_dir || worktree.is_bare || worktree.is_prunable() {
+            continue;
+        }
+        let Some(branch) = worktree.branch else {
+            continue;
+        };
 
-    // Apply safety filters to prevent deletion of new branches
-    merged_branches = apply_safety_filters(repo, merged_branches, force)?;
+        let target = merge_target(repo, &branch);
+        match repo.detect_merge(&branch, &target) {
+            Ok(Some(strategy)) => merged_branches.push(MergedBranch {
+                name: branch,
+                target,
+                strategy,
+            }),
+            Ok(None) => {}
+            Err(e) => {
+                crate::outln!(
+                    "  {} Could not check merge status of {}: {}",
+                    "⚠️".yellow(),
+                    branch,
+                    e
+                );
+            }
+        }
+    }
 
     Ok(merged_branches)
 }
 
-fn display_merged_branches(merged_branches: &[String], exclude_branch: Option<&str>) {
+fn display_merged_branches(merged_branches: &[MergedBranch], exclude: &[&str]) {
     crate::outln!("Found merged branches:");
     for branch in merged_branches {
-        crate::outln!("  - {branch}");
+        crate::outln!("  - {} ({})", branch.name, branch.strategy.label());
     }
-    if let Some(exclude) = exclude_branch {
-        crate::outln!("  (excluding: {})", exclude.cyan());
+    if !exclude.is_empty() {
+        crate::outln!("  (excluding: {})", exclude.join(", ").cyan());
     }
     crate::outln!();
 }
 
-fn process_worktrees(repo: &GitRepo, merged_branches: &[String]) -> Result<(usize, usize)> {
+fn process_worktrees(
+    repo: &GitRepo,
+    merged_branches: &[MergedBranch],
+    options: &CleanupOptions,
+) -> Result<(usize, usize)> {
     let worktrees = repo.list_worktrees()?;
     let mut cleaned_count = 0;
     let mut skipped_count = 0;
@@ -118,7 +182,7 @@ fn process_worktrees(repo: &GitRepo, merged_branches: &[String]) -> Result<(usiz
         }
 
         if let Some(branch) = &worktree.branch {
-            match process_single_worktree(repo, worktree, branch, merged_branches) {
+            match process_single_worktree(repo, worktree, branch, merged_branches, options) {
                 WorktreeAction::Removed => cleaned_count += 1,
                 WorktreeAction::Skipped => skipped_count += 1,
                 WorktreeAction::Ignored => {}
@@ -137,9 +201,10 @@ enum WorktreeAction {
 
 fn process_single_worktree(
     repo: &GitRepo,
-    worktree: &crate::git::WorktreeInfo,
+    worktree: &WorktreeInfo,
     branch: &str,
-    merged_branches: &[String],
+    merged_branches: &[MergedBranch],
+    options: &CleanupOptions,
 ) -> WorktreeAction {
     if worktree.is_detached {
         crate::outln!(
@@ -150,51 +215,89 @@ fn process_single_worktree(
         return WorktreeAction::Skipped;
     }
 
-    if !merged_branches.contains(&branch.to_string()) {
+    let Some(merged) = merged_branches.iter().find(|merged| merged.name == branch) else {
+        return WorktreeAction::Ignored;
+    };
+
+    if worktree.is_prunable() {
         return WorktreeAction::Ignored;
     }
 
-    // Additional safety check: if the worktree directory was created recently (within 24 hours),
-    // skip it to avoid deleting newly created branches
-    if let Ok(metadata) = std::fs::metadata(&worktree.path) {
-        if let Ok(created) = metadata.created() {
-            let now = SystemTime::now();
-            if let Ok(age) = now.duration_since(created) {
-                let hours_old = age.as_secs() / 3600;
-                if hours_old < 24 {
-                    crate::outln!(
-                        "{} Skipping recently created worktree: {} (created {} hours ago)",
-                        "⚠️".yellow(),
-                        branch,
-                        hours_old
-                    );
-                    return WorktreeAction::Skipped;
-                }
-            }
-        }
+    if is_locked(worktree, branch) {
+        return WorktreeAction::Skipped;
+    }
+
+    // Additional safety check: skip worktrees created within the protection
+    // window to avoid deleting newly created branches
+    if is_recently_created(repo, &worktree.path, branch, options) {
+        return WorktreeAction::Skipped;
+    }
+
+    // Merged commits are already part of the target, so only local changes matter here
+    if has_blocking_unsaved_work(repo, &worktree.path, branch, false, options) {
+        return WorktreeAction::Skipped;
     }
 
     // At this point, we've already verified this branch was actually merged
-    // The 24-hour check above provides additional safety
-    remove_worktree_and_report(repo, worktree, branch)
+    // The age check above provides additional safety
+    remove_worktree_and_report(repo, worktree, merged, options)
 }
 
 fn remove_worktree_and_report(
     repo: &GitRepo,
-    worktree: &crate::git::WorktreeInfo,
-    branch: &str,
+    worktree: &WorktreeInfo,
+    merged: &MergedBranch,
+    options: &CleanupOptions,
 ) -> WorktreeAction {
+    let branch = merged.name.as_str();
+    if options.dry_run {
+        crate::outln!(
+            "{} Would remove worktree for merged branch: {} (merged into {} via {})",
+            "🔍".blue(),
+            branch,
+            merged.target,
+            merged.strategy.label()
+        );
+        crate::outln!("    Path: {}", worktree.path.display());
+        report_sessions_to_close(&repo.root_dir, &worktree.path, branch);
+        return WorktreeAction::Removed;
+    }
+
     crate::outln!(
-        "{} Removing worktree for merged branch: {}",
+        "{} Removing worktree for merged branch: {} ({})",
         "🗑️".red(),
-        branch
+        branch,
+        merged.strategy.label()
     );
     crate::outln!("    Path: {}", worktree.path.display());
 
+    let base = merge_target(repo, branch);
+    if let Err(e) = run_removal_hooks(
+        repo,
+        &options.config,
+        HookStage::PreRemove,
+        &worktree.path,
+        branch,
+        &base,
+    ) {
+        crate::outln!("    {} Keeping worktree: {}", "❌".red(), e);
+        return WorktreeAction::Skipped;
+    }
+
     match repo.remove_worktree(&worktree.path, true) {
         Ok(_) => {
             crate::outln!("    {} Successfully removed", "✅".green());
-            stop_multiplexer_sessions(&repo.root_dir, &worktree.path);
+            stop_multiplexer_sessions(&repo.root_dir, &worktree.path, branch);
+            if let Err(e) = run_removal_hooks(
+                repo,
+                &options.config,
+                HookStage::PostRemove,
+                &worktree.path,
+                branch,
+                &base,
+            ) {
+                crate::outln!("    {} {}", "⚠️".yellow(), e);
+            }
             WorktreeAction::Removed
         }
         Err(e) => {
@@ -204,10 +307,14 @@ fn remove_worktree_and_report(
     }
 }
 
-fn display_cleanup_summary(cleaned_count: usize, skipped_count: usize) {
+fn display_cleanup_summary(cleaned_count: usize, skipped_count: usize, dry_run: bool) {
     crate::outln!();
     crate::outln!("{} Summary:", "📊".blue());
-    crate::outln!("  - Cleaned up: {cleaned_count} worktree(s)");
+    if dry_run {
+        crate::outln!("  - Would clean up: {cleaned_count} worktree(s)");
+    } else {
+        crate::outln!("  - Cleaned up: {cleaned_count} worktree(s)");
+    }
     crate::outln!("  - Skipped: {skipped_count} worktree(s)");
 
     if cleaned_count == 0 && skipped_count == 0 {
@@ -216,17 +323,28 @@ fn display_cleanup_summary(cleaned_count: usize, skipped_count: usize) {
             "{} No merged branch worktrees found to clean up",
             "✨".green()
         );
+    } else if dry_run {
+        crate::outln!();
+        crate::outln!(
+            "{} Dry run completed, nothing was removed",
+            "✅".green().bold()
+        );
     } else {
         crate::outln!();
         crate::outln!("{} Cleanup completed!", "✅".green().bold());
     }
 }
 
-fn cleanup_merged_only(repo: &GitRepo, force: bool) -> Result<()> {
-    cleanup_merged_worktrees_with_force(repo, None, force)
+fn cleanup_merged_only(repo: &GitRepo, options: &CleanupOptions) -> Result<()> {
+    let exclude: Vec<&str> = options
+        .exclude_branches
+        .iter()
+        .map(String::as_str)
+        .collect();
+    run_merged_cleanup(repo, &exclude, options)
 }
 
-fn cleanup_by_pattern(repo: &GitRepo, pattern: &str) -> Result<()> {
+fn cleanup_by_pattern(repo: &GitRepo, pattern: &str, options: &CleanupOptions) -> Result<()> {
     crate::outln!("Removing worktrees matching pattern: {}", pattern.cyan());
     crate::outln!();
 
@@ -238,31 +356,44 @@ fn cleanup_by_pattern(repo: &GitRepo, pattern: &str) -> Result<()> {
             continue;
         }
 
-        if worktree.path.to_string_lossy().contains(pattern) {
+        if worktree.path.to_string_lossy().contains(pattern) && !worktree.is_prunable() {
             if let Some(branch) = &worktree.branch {
-                remove_worktree_with_branch(repo, &worktree.path, branch)?;
-                removed_count += 1;
+                if is_locked(worktree, branch) {
+                    continue;
+                }
+                if is_recently_created(repo, &worktree.path, branch, options) {
+                    continue;
+                }
+                let reason = format!("matches pattern '{pattern}'");
+                if remove_worktree_with_branch(repo, &worktree.path, branch, &reason, options)? {
+                    removed_count += 1;
+                }
             }
         }
     }
 
     crate::outln!(
-        "{} Removed {} worktree(s) matching pattern '{}'",
+        "{} {} {} worktree(s) matching pattern '{}'",
         "✅".green(),
+        if options.dry_run {
+            "Would remove"
+        } else {
+            "Removed"
+        },
         removed_count,
         pattern
     );
     Ok(())
 }
 
-fn interactive_cleanup(repo: &GitRepo) -> Result<()> {
+fn interactive_cleanup(repo: &GitRepo, options: &CleanupOptions) -> Result<()> {
     crate::outln!("Interactive worktree removal");
     crate::outln!();
 
     let worktrees = repo.list_worktrees()?;
 
     for worktree in &worktrees {
-        if worktree.path == repo.root_dir {
+        if worktree.path == repo.root_dir || worktree.is_prunable() {
             continue;
         }
 
@@ -270,14 +401,21 @@ fn interactive_cleanup(repo: &GitRepo) -> Result<()> {
             crate::outln!("Worktree: {}", worktree.path.display());
             crate::outln!("Branch: {}", branch.cyan());
 
-            print!("Remove this worktree? (y/n) ");
-            io::stdout().flush()?;
-
-            let mut input = String::new();
-            io::stdin().read_line(&mut input)?;
+            if is_locked(worktree, branch)
+                || is_recently_created(repo, &worktree.path, branch, options)
+            {
+                crate::outln!();
+                continue;
+            }
 
-            if input.trim().to_lowercase() == "y" {
-                remove_worktree_with_branch(repo, &worktree.path, branch)?;
+            if confirm("Remove this worktree? (y/n) ")? {
+                remove_worktree_with_branch(
+                    repo,
+                    &worktree.path,
+                    branch,
+                    "selected interactively",
+                    options,
+                )?;
             } else {
                 crate::outln!("  Skipped");
             }
@@ -288,6 +426,109 @@ fn interactive_cleanup(repo: &GitRepo) -> Result<()> {
     Ok(())
 }
 
+fn confirm(prompt: &str) -> Result<bool> {
+    print!("{prompt}");
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(input.trim().to_lowercase() == "y")
+}
+
+/// Reports worktrees whose directories have disappeared and prunes their
+/// entries when `--prune` is given or the user agrees interactively.
+fn handle_prunable_worktrees(
+    repo: &GitRepo,
+    interactive: bool,
+    options: &CleanupOptions,
+) -> Result<()> {
+    let prunable: Vec<WorktreeInfo> = repo
+        .list_worktrees()?
+        .into_iter()
+        .filter(WorktreeInfo::is_prunable)
+        .collect();
+    if prunable.is_empty() {
+        return Ok(());
+    }
+
+    crate::outln!();
+    crate::outln!(
+        "{} Found {} stale worktree(s) whose directory is missing:",
+        "⚠️".yellow(),
+        prunable.len()
+    );
+    for worktree in &prunable {
+        crate::outln!(
+            "  - {} ({}): {}",
+            worktree.path.display(),
+            worktree.branch.as_deref().unwrap_or("detached"),
+            worktree.prunable.as_deref().unwrap_or_default()
+        );
+    }
+
+    if options.dry_run {
+        if options.prune || interactive {
+            crate::outln!(
+                "{} Would prune {} stale worktree(s)",
+                "🔍".blue(),
+                prunable.len()
+            );
+        }
+        return Ok(());
+    }
+
+    if options.prune || (interactive && confirm("Prune these stale worktrees? (y/n) ")?) {
+        repo.prune_worktrees()?;
+        crate::outln!(
+            "{} Pruned {} stale worktree(s)",
+            "✅".green(),
+            prunable.len()
+        );
+    } else {
+        crate::outln!("    Run `workbloom cleanup --prune` to remove them");
+    }
+
+    Ok(())
+}
+
+/// Locked worktrees are never removed. Returns true after reporting the lock.
+fn is_locked(worktree: &WorktreeInfo, branch: &str) -> bool {
+    let Some(reason) = worktree.locked.as_deref() else {
+        return false;
+    };
+
+    crate::outln!(
+        "  {} Skipping locked worktree: {} ({})",
+        "🔒".yellow(),
+        branch,
+        if reason.is_empty() {
+            "no reason given"
+        } else {
+            reason
+        }
+    );
+    crate::outln!(
+        "    Run `git worktree unlock {}` to allow removal",
+        worktree.path.display()
+    );
+    true
+}
+
+fn status_markers(worktree: &WorktreeInfo) -> String {
+    let mut markers = String::new();
+    if let Some(reason) = worktree.locked.as_deref() {
+        if reason.is_empty() {
+            markers.push_str(" [locked]");
+        } else {
+            markers.push_str(&format!(" [locked: {reason}]"));
+        }
+    }
+    if worktree.is_prunable() {
+        markers.push_str(" [missing, prunable]");
+    }
+    markers
+}
+
 fn show_status(repo: &GitRepo) -> Result<()> {
     crate::outln!("Checking merge status of all branches...");
     crate::outln!();
@@ -296,12 +537,28 @@ fn show_status(repo: &GitRepo) -> Result<()> {
 
     for worktree in &worktrees {
         if worktree.path == repo.root_dir {
-            crate::outln!("{} main (current branch)", "📍".blue());
+            let branch = worktree.branch.as_deref().unwrap_or("(detached)");
+            crate::outln!("{} {} (current branch)", "📍".blue(), branch);
         } else if let Some(branch) = &worktree.branch {
-            if repo.is_branch_merged(branch)? {
-                crate::outln!("{} {} (merged)", "✅".green(), branch);
+            let target = merge_target(repo, branch);
+            let markers = status_markers(worktree);
+            if let Some(strategy) = repo.detect_merge(branch, &target)? {
+                crate::outln!(
+                    "{} {} (merged into {} via {}){}",
+                    "✅".green(),
+                    branch,
+                    target,
+                    strategy.label(),
+                    markers
+                );
             } else {
-                crate::outln!("{} {} (not merged)", "❌".red(), branch);
+                crate::outln!(
+                    "{} {} (not merged into {}){}",
+                    "❌".red(),
+                    branch,
+                    target,
+                    markers
+                );
             }
         }
     }
@@ -309,16 +566,56 @@ fn show_status(repo: &GitRepo) -> Result<()> {
     Ok(())
 }
 
-fn remove_worktree_with_branch(repo: &GitRepo, path: &std::path::Path, branch: &str) -> Result<()> {
+fn remove_worktree_with_branch(
+    repo: &GitRepo,
+    path: &std::path::Path,
+    branch: &str,
+    reason: &str,
+    options: &CleanupOptions,
+) -> Result<bool> {
+    // The branch is deleted too, so commits missing from every remote would be lost
+    if has_blocking_unsaved_work(repo, path, branch, true, options) {
+        return Ok(false);
+    }
+
+    if options.dry_run {
+        crate::outln!(
+            "  {} Would remove worktree: {} ({})",
+            "🔍".blue(),
+            path.display(),
+            reason
+        );
+        report_sessions_to_close(&repo.root_dir, path, branch);
+        if repo.branch_exists(branch)? {
+            crate::outln!("    {} Would delete branch: {}", "🔍".blue(), branch);
+        }
+        return Ok(true);
+    }
+
     crate::outln!("  Removing worktree: {}", path.display());
 
+    let base = merge_target(repo, branch);
+    if let Err(e) = run_removal_hooks(
+        repo,
+        &options.config,
+        HookStage::PreRemove,
+        path,
+        branch,
+        &base,
+    ) {
+        crate::outln!("  {} Keeping worktree: {}", "❌".red(), e);
+        return Ok(false);
+    }
+
+    // Unsaved work has been checked above; force is still needed because files
+    // copied in by workbloom show up as untracked
     if let Err(e) = repo.remove_worktree(path, true) {
         crate::outln!("  {} Failed to remove worktree: {}", "❌".red(), e);
-        return Ok(());
+        return Ok(false);
     }
 
     crate::outln!("  {} Worktree removed successfully", "✅".green());
-    stop_multiplexer_sessions(&repo.root_dir, path);
+    stop_multiplexer_sessions(&repo.root_dir, path, branch);
 
     if repo.branch_exists(branch)? {
         if let Err(e) = repo.delete_branch(branch) {
@@ -330,17 +627,185 @@ fn remove_worktree_with_branch(repo: &GitRepo, path: &std::path::Path, branch: &
             );
         } else {
             crate::outln!("  {} Branch '{}' deleted", "✅".green(), branch);
+            if let Err(e) = WorktreeMetadata::remove(repo, branch) {
+                crate::outln!("  {} Could not remove metadata: {}", "⚠️".yellow(), e);
+            }
         }
     }
 
-    Ok(())
+    if let Err(e) = run_removal_hooks(
+        repo,
+        &options.config,
+        HookStage::PostRemove,
+        path,
+        branch,
+        &base,
+    ) {
+        crate::outln!("  {} {}", "⚠️".yellow(), e);
+    }
+
+    Ok(true)
+}
+
+/// Runs the `.workbloom` hooks for a worktree that cleanup is removing. A failing
+/// pre_remove hook keeps the worktree; post_remove failures are only reported.
+fn run_removal_hooks(
+    repo: &GitRepo,
+    config: &Config,
+    stage: HookStage,
+    worktree_path: &std::path::Path,
+    branch: &str,
+    base: &str,
+) -> Result<()> {
+    let session = multiplexer::session_name(&repo.root_dir, &worktree_dir_name(branch));
+    let context = HookContext {
+        branch,
+        worktree: worktree_path,
+        repo_root: &repo.root_dir,
+        base: Some(base),
+        session: &session,
+    };
+    hooks::run_hooks(config, stage, &context)
+}
+
+/// Returns true when the worktree was created within the protection window and
+/// must be kept.
+fn is_recently_created(
+    repo: &GitRepo,
+    worktree_path: &std::path::Path,
+    branch: &str,
+    options: &CleanupOptions,
+) -> bool {
+    let protect_hours = options
+        .protect_hours
+        .or(options.config.protect_hours)
+        .unwrap_or(DEFAULT_PROTECT_HOURS);
+    if protect_hours == 0 {
+        return false;
+    }
+
+    let Some(age) = metadata::worktree_age(repo, worktree_path, branch) else {
+        return false;
+    };
+
+    if age >= Duration::from_secs(protect_hours.saturating_mul(3600)) {
+        return false;
+    }
+
+    let created_by = WorktreeMetadata::load(repo, branch)
+        .ok()
+        .flatten()
+        .map(|m| m.creator)
+        .filter(|creator| !creator.is_empty())
+        .map(|creator| format!(" by {creator}"))
+        .unwrap_or_default();
+    crate::outln!(
+        "  {} Skipping recently created worktree: {} (created {} hours ago{}, protected for {} hours)",
+        "🔒".yellow(),
+        branch,
+        age.as_secs() / 3600,
+        created_by,
+        protect_hours
+    );
+    crate::outln!("    Use --protect-hours 0 to remove it anyway");
+    true
+}
+
+/// Checks a worktree for work that removal would destroy. Returns true when the
+/// worktree must be kept, i.e. unsaved work was found and not explicitly discarded.
+fn has_blocking_unsaved_work(
+    repo: &GitRepo,
+    worktree_path: &std::path::Path,
+    branch: &str,
+    check_unpushed: bool,
+    options: &CleanupOptions,
+) -> bool {
+    let unsaved =
+        match find_unsaved_work(repo, &options.config, worktree_path, branch, check_unpushed) {
+            Ok(unsaved) => unsaved,
+            Err(e) => {
+                crate::outln!(
+                    "  {} Could not check {} for unsaved work: {}",
+                    "⚠️".yellow(),
+                    branch,
+                    e
+                );
+                return !options.discard_unsaved;
+            }
+        };
+
+    if unsaved.is_empty() {
+        return false;
+    }
+
+    let details = unsaved
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    if options.discard_unsaved {
+        crate::outln!(
+            "  {} Discarding unsaved work in {}: {}",
+            "⚠️".yellow(),
+            branch,
+            details
+        );
+        return false;
+    }
+
+    crate::outln!(
+        "  {} Skipping worktree with unsaved work: {} ({})",
+        "🔒".yellow(),
+        branch,
+        details
+    );
+    crate::outln!("    Use --discard-unsaved to remove it anyway");
+    true
 }
 
-fn apply_safety_filters(
+fn find_unsaved_work(
     repo: &GitRepo,
-    branches: Vec<String>,
-    _force: bool,
-) -> Result<Vec<String>> {
+    config: &Config,
+    worktree_path: &std::path::Path,
+    branch: &str,
+    check_unpushed: bool,
+) -> Result<Vec<UnsavedWork>> {
+    let mut unsaved = Vec::new();
+
+    if worktree_path.exists() {
+        let changes = repo.worktree_changes(worktree_path)?;
+        if !changes.changed.is_empty() {
+            unsaved.push(UnsavedWork::UncommittedChanges(changes.changed.len()));
+        }
+
+        // Files workbloom copied in are reproducible, so they don't count
+        let untracked = changes
+            .untracked
+            .iter()
+            .filter(|path| !config.is_copied_path(path))
+            .count();
+        if untracked > 0 {
+            unsaved.push(UnsavedWork::UntrackedFiles(untracked));
+        }
+    }
+
+    let stashes = repo.stash_count(branch)?;
+    if stashes > 0 {
+        unsaved.push(UnsavedWork::Stashes(stashes));
+    }
+
+    if check_unpushed {
+        let unpushed = repo.unpushed_commit_count(branch)?;
+        if unpushed > 0 {
+            unsaved.push(UnsavedWork::UnpushedCommits(unpushed));
+        }
+    }
+
+    Ok(unsaved)
+}
+
+fn apply_safety_filters(repo: &GitRepo, branches: Vec<MergedBranch>) -> Result<Vec<MergedBranch>> {
     if branches.is_empty() {
         return Ok(branches);
     }
@@ -350,24 +815,42 @@ fn apply_safety_filters(
     filter_identical_commits(repo, branches)
 }
 
-fn filter_identical_commits(repo: &GitRepo, branches: Vec<String>) -> Result<Vec<String>> {
-    // Get main branch head for comparison
-    let main_head = get_branch_head(repo, "main")?;
+fn filter_identical_commits(
+    repo: &GitRepo,
+    branches: Vec<MergedBranch>,
+) -> Result<Vec<MergedBranch>> {
+    // Get default branch head for comparison
+    let main_head = repo.resolve_commit(&repo.default_branch)?;
     let mut safe_branches = Vec::new();
 
-    for branch in branches {
-        // Safety check: Don't delete branches that point to the same commit as main
-        // This protects newly created branches with no commits
-        match get_branch_head(repo, &branch) {
+    for merged in branches {
+        let branch = merged.name.as_str();
+        // Safety check: Don't delete branches that point to the same commit as the
+        // default branch. This protects newly created branches with no commits
+        match repo.resolve_commit(branch) {
             Ok(branch_head) => {
                 if branch_head == main_head {
                     crate::outln!(
-                        "  {} Skipping new branch (same as main): {}",
+                        "  {} Skipping new branch (same as {}): {}",
                         "🔒".yellow(),
+                        repo.default_branch,
                         branch
                     );
                     continue;
                 }
+
+                // Also protect branches with no commits since their recorded start point
+                if let Ok(Some(metadata)) = WorktreeMetadata::load(repo, branch) {
+                    if metadata.base_commit.as_deref() == Some(branch_head.as_str()) {
+                        crate::outln!(
+                            "  {} Skipping new branch (no commits since {}): {}",
+                            "🔒".yellow(),
+                            metadata.base.as_deref().unwrap_or("its start point"),
+                            branch
+                        );
+                        continue;
+                    }
+                }
             }
             Err(_) => {
                 crate::outln!(
@@ -379,32 +862,77 @@ fn filter_identical_commits(repo: &GitRepo, branches: Vec<String>) -> Result<Vec
             }
         }
 
-        safe_branches.push(branch);
+        safe_branches.push(merged);
     }
 
     Ok(safe_branches)
 }
 
-fn get_branch_head(repo: &GitRepo, branch_name: &str) -> Result<String> {
-    let output = std::process::Command::new("git")
-        .args(["rev-parse", branch_name])
-        .current_dir(&repo.root_dir)
-        .output()
-        .context("Failed to get branch HEAD")?;
-
-    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+/// Branch a worktree branch is expected to be merged into: the base recorded
+/// when workbloom created it, or the default branch.
+pub(crate) fn merge_target(repo: &GitRepo, branch: &str) -> String {
+    WorktreeMetadata::load(repo, branch)
+        .ok()
+        .flatten()
+        .and_then(|metadata| metadata.base)
+        .filter(|base| repo.is_branch_ref(base))
+        .unwrap_or_else(|| repo.default_branch.clone())
 }
 
-fn stop_multiplexer_sessions(repo_root: &std::path::Path, worktree_path: &std::path::Path) {
+/// Multiplexer sessions that may belong to a worktree, as `(backend, name, legacy)`.
+pub(crate) fn session_candidates(
+    repo_root: &std::path::Path,
+    worktree_path: &std::path::Path,
+    branch: &str,
+) -> Vec<(multiplexer::Backend, String, bool)> {
+    // Sessions are named after the branch; older versions named them after the
+    // worktree directory, which differs when a custom worktree layout is used.
+    let mut identifiers = vec![worktree_dir_name(branch)];
     if let Some(dir_name) = worktree_path.file_name().and_then(|n| n.to_str()) {
-        let session_name = multiplexer::session_name(repo_root, dir_name);
+        if !identifiers.iter().any(|id| id == dir_name) {
+            identifiers.push(dir_name.to_string());
+        }
+    }
+
+    let mut candidates = Vec::new();
+    for identifier in &identifiers {
+        let session_name = multiplexer::session_name(repo_root, identifier);
         for backend in multiplexer::available_backends() {
-            try_stop_session(backend, &session_name, false);
+            candidates.push((backend, session_name.clone(), false));
         }
 
-        let legacy_name = multiplexer::legacy_session_name(repo_root, dir_name);
+        let legacy_name = multiplexer::legacy_session_name(repo_root, identifier);
         if legacy_name != session_name && multiplexer::is_available(multiplexer::Backend::Tmux) {
-            try_stop_session(multiplexer::Backend::Tmux, &legacy_name, true);
+            candidates.push((multiplexer::Backend::Tmux, legacy_name, true));
+        }
+    }
+    candidates
+}
+
+fn stop_multiplexer_sessions(
+    repo_root: &std::path::Path,
+    worktree_path: &std::path::Path,
+    branch: &str,
+) {
+    for (backend, session_name, legacy) in session_candidates(repo_root, worktree_path, branch) {
+        try_stop_session(backend, &session_name, legacy);
+    }
+}
+
+fn report_sessions_to_close(
+    repo_root: &std::path::Path,
+    worktree_path: &std::path::Path,
+    branch: &str,
+) {
+    for (backend, session_name, legacy) in session_candidates(repo_root, worktree_path, branch) {
+        if multiplexer::session_exists(backend, &session_name).unwrap_or(false) {
+            crate::outln!(
+                "    {} Would close {}{} session: {}",
+                "🔍".blue(),
+                if legacy { "legacy " } else { "" },
+                backend.display_name(),
+                session_name
+            );
         }
     }
 }
@@ -571,6 +1099,91 @@ mod tests {
         drop(guard);
     }
 
+    #[test]
+    fn age_guard_uses_recorded_creation_time() -> Result<()> {
+        let temp_dir = tempfile::TempDir::new()?;
+        std::process::Command::new("git")
+            .args(["init"])
+            .current_dir(temp_dir.path())
+            .output()?;
+        let repo = GitRepo::open(temp_dir.path().to_path_buf());
+        let worktree_path = temp_dir.path().join("worktree-feature");
+
+        let mut metadata = WorktreeMetadata::new(&repo, "feature", None);
+        metadata.save(&repo)?;
+        let options = CleanupOptions::default();
+        assert!(is_recently_created(
+            &repo,
+            &worktree_path,
+            "feature",
+            &options
+        ));
+
+        let disabled = CleanupOptions {
+            protect_hours: Some(0),
+            ..CleanupOptions::default()
+        };
+        assert!(!is_recently_created(
+            &repo,
+            &worktree_path,
+            "feature",
+            &disabled
+        ));
+
+        let configured_off = CleanupOptions {
+            config: Config {
+                protect_hours: Some(0),
+                ..Config::default()
+            },
+            ..CleanupOptions::default()
+        };
+        assert!(!is_recently_created(
+            &repo,
+            &worktree_path,
+            "feature",
+            &configured_off
+        ));
+
+        let forever = CleanupOptions {
+            protect_hours: Some(u64::MAX),
+            ..CleanupOptions::default()
+        };
+        assert!(is_recently_created(
+            &repo,
+            &worktree_path,
+            "feature",
+            &forever
+        ));
+
+        metadata.created_at -= 25 * 3600;
+        metadata.save(&repo)?;
+        assert!(!is_recently_created(
+            &repo,
+            &worktree_path,
+            "feature",
+            &options
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn report_sessions_to_close_does_not_kill_sessions() {
+        let repo_root = PathBuf::from("/tmp/repo-dry-run");
+        let worktree_path = repo_root.join("worktree-feature");
+        let primary = multiplexer::session_name(&repo_root, "worktree-feature");
+
+        let mock = Arc::new(
+            MockCleanupMultiplexer::new(&[Backend::Zellij, Backend::Tmux])
+                .with_session(Backend::Zellij, &primary),
+        );
+
+        with_mock_multiplexer(mock.clone(), || {
+            report_sessions_to_close(&repo_root, &worktree_path, "feature");
+        });
+
+        assert!(mock.killed_sessions().is_empty());
+    }
+
     #[test]
     fn stop_multiplexer_sessions_closes_zellij_and_tmux_sessions() {
         let repo_root = PathBuf::from("/tmp/repo-stop");
@@ -586,7 +1199,7 @@ mod tests {
         );
 
         with_mock_multiplexer(mock.clone(), || {
-            stop_multiplexer_sessions(&repo_root, &worktree_path);
+            stop_multiplexer_sessions(&repo_root, &worktree_path, "feature");
         });
 
         assert_eq!(
@@ -0,0 +1,275 @@
+use anyhow::Result;
+use colored::*;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use crate::commands::cleanup::{merge_target, session_candidates};
+use crate::config::Config;
+use crate::git::{GitRepo, WorktreeInfo};
+use crate::{metadata, multiplexer, output};
+
+#[derive(Debug, Clone, Default)]
+pub struct ListOptions {
+    /// Overrides the configured or detected default branch.
+    pub default_branch: Option<String>,
+    /// Print machine-readable JSON instead of a table.
+    pub json: bool,
+}
+
+pub fn execute(options: &ListOptions) -> Result<()> {
+    let mut repo = GitRepo::new()?;
+    let config = Config::load_from_file(&repo.root_dir).unwrap_or_else(|_| Config::default());
+    config.warn_invalid_settings();
+    repo.set_default_branch(options.default_branch.clone().or(config.default_branch))?;
+
+    let entries = collect_entries(&repo)?;
+
+    if options.json {
+        output::print_data(format_args!("{}", serde_json::to_string_pretty(&entries)?));
+    } else if entries.is_empty() {
+        crate::outln!("{} No worktrees found", "✨".green());
+    } else {
+        print_table(&repo.root_dir, &entries);
+    }
+
+    Ok(())
+}
+
+/// One row of `workbloom list`; also the `--json` schema.
+#[derive(Debug, Clone, Serialize)]
+pub struct WorktreeEntry {
+    pub path: PathBuf,
+    pub branch: Option<String>,
+    pub head: Option<String>,
+    /// True for the main worktree.
+    pub main: bool,
+    /// Branch ahead/behind counts are measured against.
+    pub base: Option<String>,
+    pub ahead: Option<usize>,
+    pub behind: Option<usize>,
+    /// Uncommitted changes or untracked files; None when the directory is missing.
+    pub dirty: Option<bool>,
+    /// Seconds since the worktree was created, when known.
+    pub age_secs: Option<u64>,
+    pub locked: bool,
+    pub lock_reason: Option<String>,
+    pub prunable: bool,
+    /// Multiplexer backends with a live session for this worktree.
+    pub sessions: Vec<String>,
+}
+
+fn collect_entries(repo: &GitRepo) -> Result<Vec<WorktreeEntry>> {
+    let mut entries = Vec::new();
+
+    for worktree in repo.list_worktrees()? {
+        if worktree.is_bare {
+            continue;
+        }
+        entries.push(build_entry(repo, worktree));
+    }
+
+    Ok(entries)
+}
+
+fn build_entry(repo: &GitRepo, worktree: WorktreeInfo) -> WorktreeEntry {
+    let main = worktree.path == repo.root_dir;
+    let dirty = (!worktree.is_prunable())
+        .then(|| repo.worktree_changes(&worktree.path).ok())
+        .flatten()
+        .map(|changes| !changes.changed.is_empty() || !changes.untracked.is_empty());
+
+    let mut entry = WorktreeEntry {
+        path: worktree.path.clone(),
+        branch: worktree.branch.clone(),
+        head: worktree.head.clone(),
+        main,
+        base: None,
+        ahead: None,
+        behind: None,
+        dirty,
+        age_secs: None,
+        locked: worktree.is_locked(),
+        lock_reason: worktree.locked.clone().filter(|reason| !reason.is_empty()),
+        prunable: worktree.is_prunable(),
+        sessions: Vec::new(),
+    };
+
+    let Some(branch) = worktree.branch.as_deref() else {
+        return entry;
+    };
+    if main {
+        return entry;
+    }
+
+    let base = merge_target(repo, branch);
+    if let Ok((ahead, behind)) = repo.ahead_behind(branch, &base) {
+        entry.ahead = Some(ahead);
+        entry.behind = Some(behind);
+    }
+    entry.base = Some(base);
+    entry.age_secs = metadata::worktree_age(repo, &worktree.path, branch).map(|age| age.as_secs());
+    entry.sessions = live_sessions(&repo.root_dir, &worktree.path, branch);
+    entry
+}
+
+fn live_sessions(repo_root: &Path, worktree_path: &Path, branch: &str) -> Vec<String> {
+    let mut sessions = Vec::new();
+    for (backend, session_name, _) in session_candidates(repo_root, worktree_path, branch) {
+        let name = backend.display_name().to_lowercase();
+        if !sessions.contains(&name)
+            && multiplexer::session_exists(backend, &session_name).unwrap_or(false)
+        {
+            sessions.push(name);
+        }
+    }
+    sessions
+}
+
+fn print_table(repo_root: &Path, entries: &[WorktreeEntry]) {
+    let header = [
+        "BRANCH", "PATH", "HEAD", "BASE", "+/-", "STATE", "AGE", "SESSION",
+    ];
+    let rows: Vec<[String; 8]> = entries
+        .iter()
+        .map(|entry| table_row(repo_root, entry))
+        .collect();
+
+    let mut widths = header.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let format_row = |cells: &[String]| {
+        cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    output::print_data(format_args!(
+        "{}",
+        format_row(&header.map(String::from)).bold()
+    ));
+    for row in &rows {
+        output::print_data(format_args!("{}", format_row(row)));
+    }
+}
+
+fn table_row(repo_root: &Path, entry: &WorktreeEntry) -> [String; 8] {
+    let branch = match (&entry.branch, entry.main) {
+        (Some(branch), true) => format!("{branch} (main)"),
+        (Some(branch), false) => branch.clone(),
+        (None, _) => "(detached)".to_string(),
+    };
+    let path = entry
+        .path
+        .strip_prefix(repo_root)
+        .ok()
+        .filter(|relative| !relative.as_os_str().is_empty())
+        .unwrap_or(&entry.path)
+        .display()
+        .to_string();
+    let head = entry
+        .head
+        .as_deref()
+        .map(|head| head.chars().take(7).collect())
+        .unwrap_or_else(|| "-".to_string());
+    let ahead_behind = match (entry.ahead, entry.behind) {
+        (Some(ahead), Some(behind)) => format!("+{ahead}/-{behind}"),
+        _ => "-".to_string(),
+    };
+
+    let mut state = Vec::new();
+    match entry.dirty {
+        Some(true) => state.push("dirty".to_string()),
+        Some(false) => state.push("clean".to_string()),
+        None => {}
+    }
+    if entry.locked {
+        state.push(match &entry.lock_reason {
+            Some(reason) => format!("locked: {reason}"),
+            None => "locked".to_string(),
+        });
+    }
+    if entry.prunable {
+        state.push("missing".to_string());
+    }
+
+    [
+        branch,
+        path,
+        head,
+        entry.base.clone().unwrap_or_else(|| "-".to_string()),
+        ahead_behind,
+        state.join(", "),
+        entry
+            .age_secs
+            .map(format_age)
+            .unwrap_or_else(|| "-".to_string()),
+        if entry.sessions.is_empty() {
+            "-".to_string()
+        } else {
+            entry.sessions.join(",")
+        },
+    ]
+}
+
+fn format_age(secs: u64) -> String {
+    match secs {
+        s if s < 3600 => format!("{}m", s / 60),
+        s if s < 86_400 => format!("{}h", s / 3600),
+        s => format!("{}d", s / 86_400),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_age_picks_largest_unit() {
+        assert_eq!(format_age(59), "0m");
+        assert_eq!(format_age(3 * 3600 + 5), "3h");
+        assert_eq!(format_age(2 * 86_400), "2d");
+    }
+
+    #[test]
+    fn table_row_shows_relative_path_and_state() {
+        let entry = WorktreeEntry {
+            path: PathBuf::from("/repo/worktree-feature"),
+            branch: Some("feature".to_string()),
+            head: Some("0123456789abcdef".to_string()),
+            main: false,
+            base: Some("main".to_string()),
+            ahead: Some(2),
+            behind: Some(1),
+            dirty: Some(true),
+            age_secs: Some(7200),
+            locked: true,
+            lock_reason: Some("usb".to_string()),
+            prunable: false,
+            sessions: vec!["zellij".to_string()],
+        };
+
+        assert_eq!(
+            table_row(Path::new("/repo"), &entry),
+            [
+                "feature",
+                "worktree-feature",
+                "0123456",
+                "main",
+                "+2/-1",
+                "dirty, locked: usb",
+                "2h",
+                "zellij"
+            ]
+            .map(String::from)
+        );
+    }
+}
@@ -1,2 +1,5 @@
+pub mod allow;
 pub mod cleanup;
+pub mod list;
 pub mod setup;
+pub mod switch;
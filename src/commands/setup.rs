@@ -1,59 +1,105 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use colored::*;
 use indicatif::{ProgressBar, ProgressStyle};
+use serde::Serialize;
 use std::env;
 use std::fs;
+use std::io::{self, IsTerminal, Write};
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{Command, Stdio};
+use std::sync::{Mutex, PoisonError};
+use std::thread;
 use std::time::Duration;
 
-use crate::{config::Config, file_ops, git::GitRepo, multiplexer};
+use crate::config::{worktree_dir_name, Config, ScriptInterpreter, ScriptSource, SetupCleanup};
+use crate::hooks::{self, HookContext, HookStage};
+use crate::interrupt::{self, InterruptGuard};
+use crate::metadata::{self, WorktreeMetadata};
+use crate::script::{self, ScriptStatus};
+use crate::trust::TrustStore;
+use crate::{file_ops, git::GitRepo, multiplexer, output};
 
 const PROGRESS_STEPS: u64 = 4;
 
-pub fn execute(
-    branch_name: &str,
-    start_shell: bool,
-    use_multiplexer: bool,
-    print_path: bool,
-) -> Result<()> {
-    let repo = GitRepo::new()?;
-    let config = Config::load_from_file(&repo.root_dir).unwrap_or_else(|_| Config::default());
+/// Serializes steps that write to the repository's refs, config and worktree
+/// list, which git guards with lock files that concurrent batch setups would
+/// otherwise collide on.
+static GIT_LOCK: Mutex<()> = Mutex::new(());
+
+/// Keeps concurrent batch setups from prompting on the terminal at the same time.
+static PROMPT_LOCK: Mutex<()> = Mutex::new(());
+
+pub struct SetupOptions {
+    pub start_shell: bool,
+    pub use_multiplexer: bool,
+    pub print_path: bool,
+    /// Ref a newly created branch starts from. Defaults to the repository's default branch.
+    pub start_point: Option<String>,
+    /// Fetch the start point from origin before creating the branch.
+    pub fetch: bool,
+    /// Overrides the configured or detected default branch.
+    pub default_branch: Option<String>,
+    /// Re-copy configured files when the worktree already exists.
+    pub sync: bool,
+    /// Leave a half-built worktree and branch in place when setup fails.
+    pub keep_on_failure: bool,
+    /// Overrides the configured setup script timeout, in seconds. 0 disables it.
+    pub script_timeout: Option<u64>,
+    /// Fail setup when the setup script fails, regardless of configuration.
+    pub strict: bool,
+    /// Skip the merged-worktree cleanup, regardless of configuration.
+    pub no_cleanup: bool,
+}
 
-    let worktree_dir_name = format!("worktree-{}", branch_name.replace('/', "-"));
-    let worktree_path = repo.root_dir.join(&worktree_dir_name);
-    let display_worktree_path = display_worktree_path(&repo.root_dir, &worktree_dir_name);
-    let session_name = multiplexer::session_name(&repo.root_dir, &worktree_dir_name);
+pub fn execute(branch_name: &str, options: &SetupOptions) -> Result<()> {
+    let SetupOptions {
+        start_shell,
+        use_multiplexer,
+        print_path,
+        ..
+    } = *options;
+    let (repo, config) = open_repo(options)?;
+
+    let session_name = multiplexer::session_name(&repo.root_dir, &worktree_dir_name(branch_name));
+
+    if let Some(existing) = find_existing_worktree(&repo, branch_name)? {
+        refresh_existing_worktree(&repo, &config, branch_name, &existing, options)?;
+        return handle_post_setup(
+            print_path,
+            start_shell,
+            use_multiplexer,
+            &display_worktree_path(&repo.root_dir, &existing),
+            &existing,
+            &session_name,
+            None,
+        );
+    }
+
+    let worktree_path = new_worktree_path(&repo, &config, branch_name)?;
+    let display_worktree_path = display_worktree_path(&repo.root_dir, &worktree_path);
 
     crate::outln!("{} Setting up git worktree...", "🌲".green());
     crate::outln!("Branch: {}", branch_name.cyan());
     crate::outln!("Worktree directory: {}", worktree_path.display());
     crate::outln!();
 
-    run_cleanup_if_exists(&repo, Some(branch_name))?;
+    let cleanup = cleanup_timing(&config, options);
+    if cleanup == SetupCleanup::Before {
+        run_cleanup_if_exists(&repo, &config, &[branch_name])?;
+    }
 
     let pb = build_progress_bar(print_path);
-
-    pb.set_message("Checking branch...");
-    ensure_branch_ready(&repo, branch_name)?;
-    pb.inc(1);
-
-    pb.set_message("Creating worktree...");
-    crate::outln!("{} Creating git worktree...", "🔧".blue());
-    repo.add_worktree(&worktree_path, branch_name)?;
-    pb.inc(1);
-
-    pb.set_message("Copying files...");
-    crate::outln!("{} Copying required files...", "📦".blue());
-    file_ops::copy_required_files(&repo.root_dir, &worktree_path, &config)?;
-    pb.inc(1);
-
-    pb.set_message("Running setup script...");
-    run_setup_script(&worktree_path)?;
-
-    pb.set_message("Setting up direnv...");
-    file_ops::setup_direnv(&worktree_path)?;
-    pb.inc(1);
+    let interrupt_guard = InterruptGuard::install();
+    let created_session = create_worktree(
+        &repo,
+        &config,
+        branch_name,
+        &worktree_path,
+        &session_name,
+        options,
+        &pb,
+    )?;
+    drop(interrupt_guard);
 
     pb.finish_with_message("Setup completed!");
 
@@ -66,6 +112,13 @@ pub fn execute(
     );
     crate::outln!();
 
+    // Background cleanup detaches once the path has been printed; a shell only
+    // returns when the user leaves it, so it cannot wait for that
+    let cleanup_first = cleanup == SetupCleanup::After || start_shell;
+    if cleanup_first {
+        run_deferred_cleanup(&repo, &config, cleanup, &[branch_name], options);
+    }
+
     handle_post_setup(
         print_path,
         start_shell,
@@ -73,62 +126,753 @@ pub fn execute(
         &display_worktree_path,
         &worktree_path,
         &session_name,
+        created_session,
     )?;
 
+    if !cleanup_first {
+        run_deferred_cleanup(&repo, &config, cleanup, &[branch_name], options);
+    }
+
     Ok(())
 }
 
-fn run_setup_script(worktree_path: &std::path::Path) -> Result<()> {
-    let setup_script_path = worktree_path.join(".workbloom-setup.sh");
+/// Outcome of setting up one branch of a batch.
+#[derive(Serialize)]
+struct BatchEntry {
+    branch: String,
+    status: BatchStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
 
-    if setup_script_path.exists() {
-        crate::outln!("{} Found .workbloom-setup.sh, executing...", "🚀".cyan());
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum BatchStatus {
+    Created,
+    Reused,
+    Failed,
+}
 
-        // Make the script executable
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            let mut perms = std::fs::metadata(&setup_script_path)?.permissions();
-            perms.set_mode(0o755);
-            std::fs::set_permissions(&setup_script_path, perms)?;
+/// Sets up worktrees for several branches at once. New worktrees are created in
+/// parallel, the merged cleanup runs once, and a failing branch is reported
+/// without aborting the others. Prints one path per line, or JSON with `json`.
+pub fn execute_batch(branch_names: &[String], options: &SetupOptions, json: bool) -> Result<()> {
+    if options.start_shell {
+        bail!("--shell can only be used when setting up a single branch");
+    }
+    let (repo, config) = open_repo(options)?;
+
+    let mut branches: Vec<&str> = Vec::new();
+    for branch in branch_names {
+        if !branches.contains(&branch.as_str()) {
+            branches.push(branch);
         }
+    }
 
-        // Execute the script
-        let output = Command::new("bash")
-            .arg(&setup_script_path)
-            .current_dir(worktree_path)
-            .output()
-            .context("Failed to execute .workbloom-setup.sh")?;
+    let cleanup = cleanup_timing(&config, options);
+    if cleanup == SetupCleanup::Before {
+        run_cleanup_if_exists(&repo, &config, &branches)?;
+    }
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            eprintln!(
-                "{} Warning: .workbloom-setup.sh failed: {}",
-                "⚠️".yellow(),
-                stderr
+    crate::outln!(
+        "{} Setting up {} worktrees...",
+        "🌲".green(),
+        branches.len()
+    );
+
+    // Existing worktrees are resolved up front because pruning stale entries
+    // changes state shared by all branches
+    let plans: Vec<(&str, Result<Option<PathBuf>>)> = branches
+        .iter()
+        .map(|&branch| (branch, find_existing_worktree(&repo, branch)))
+        .collect();
+
+    let interrupt_guard = InterruptGuard::install();
+    let entries: Vec<BatchEntry> = thread::scope(|scope| {
+        let handles: Vec<_> = plans
+            .into_iter()
+            .map(|(branch, existing)| {
+                let (repo, config) = (&repo, &config);
+                scope.spawn(move || {
+                    output::set_line_prefix(Some(format!("[{branch}] ")));
+                    let result = existing.and_then(|existing| match existing {
+                        Some(path) => {
+                            refresh_existing_worktree(repo, config, branch, &path, options)
+                                .map(|()| (BatchStatus::Reused, path))
+                        }
+                        None => {
+                            let path = new_worktree_path(repo, config, branch)?;
+                            let session_name = multiplexer::session_name(
+                                &repo.root_dir,
+                                &worktree_dir_name(branch),
+                            );
+                            let pb = ProgressBar::hidden();
+                            create_worktree(
+                                repo,
+                                config,
+                                branch,
+                                &path,
+                                &session_name,
+                                options,
+                                &pb,
+                            )
+                            .map(|_| (BatchStatus::Created, path))
+                        }
+                    });
+
+                    match result {
+                        Ok((status, path)) => BatchEntry {
+                            branch: branch.to_string(),
+                            status,
+                            path: Some(display_worktree_path(&repo.root_dir, &path)),
+                            error: None,
+                        },
+                        Err(e) => BatchEntry {
+                            branch: branch.to_string(),
+                            status: BatchStatus::Failed,
+                            path: None,
+                            error: Some(format!("{e:#}")),
+                        },
+                    }
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("setup worker panicked"))
+            .collect()
+    });
+    drop(interrupt_guard);
+
+    if cleanup == SetupCleanup::After {
+        run_deferred_cleanup(&repo, &config, cleanup, &branches, options);
+    }
+    print_batch_results(&entries, options.print_path, json)?;
+    if cleanup == SetupCleanup::Background {
+        run_deferred_cleanup(&repo, &config, cleanup, &branches, options);
+    }
+
+    let failed = entries
+        .iter()
+        .filter(|entry| entry.status == BatchStatus::Failed)
+        .count();
+    if failed > 0 {
+        bail!("{failed} of {} branches failed to set up", entries.len());
+    }
+    Ok(())
+}
+
+fn print_batch_results(entries: &[BatchEntry], print_path: bool, json: bool) -> Result<()> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(entries)?);
+        return Ok(());
+    }
+
+    crate::outln!();
+    for entry in entries {
+        match (&entry.path, &entry.error) {
+            (Some(path), _) => {
+                crate::outln!(
+                    "{} {}: {}",
+                    "✅".green(),
+                    entry.branch.cyan(),
+                    path.display()
+                )
+            }
+            (None, Some(error)) => {
+                crate::outln!("{} {}: {}", "❌".red(), entry.branch.cyan(), error)
+            }
+            (None, None) => {}
+        }
+    }
+
+    if print_path {
+        for path in entries.iter().filter_map(|entry| entry.path.as_ref()) {
+            println!("{}", path.display());
+        }
+    }
+    Ok(())
+}
+
+fn open_repo(options: &SetupOptions) -> Result<(GitRepo, Config)> {
+    let mut repo = GitRepo::new()?;
+    let config = Config::load_from_file(&repo.root_dir).unwrap_or_else(|_| Config::default());
+    config.warn_invalid_settings();
+    repo.set_default_branch(
+        options
+            .default_branch
+            .clone()
+            .or_else(|| config.default_branch.clone()),
+    )?;
+    Ok((repo, config))
+}
+
+/// Where a new worktree for `branch_name` goes. Fails when something that is
+/// not its worktree is already there.
+fn new_worktree_path(repo: &GitRepo, config: &Config, branch_name: &str) -> Result<PathBuf> {
+    let worktree_path = config.worktree_path(&repo.root_dir, branch_name);
+    if worktree_path.exists() {
+        bail!(
+            "{} already exists but is not a worktree for '{}'. Remove it or set worktree_dir in .workbloom",
+            worktree_path.display(),
+            branch_name
+        );
+    }
+    Ok(worktree_path)
+}
+
+fn cleanup_timing(config: &Config, options: &SetupOptions) -> SetupCleanup {
+    if options.no_cleanup {
+        SetupCleanup::Off
+    } else {
+        config.setup_cleanup
+    }
+}
+
+/// Runs the merged cleanup once new worktrees are ready, when configured to run
+/// after setup. Failures only produce a warning.
+fn run_deferred_cleanup(
+    repo: &GitRepo,
+    config: &Config,
+    cleanup: SetupCleanup,
+    exclude: &[&str],
+    options: &SetupOptions,
+) {
+    match cleanup {
+        SetupCleanup::After => {
+            if let Err(e) = run_cleanup_if_exists(repo, config, exclude) {
+                crate::outln!("{} Cleanup failed: {}", "⚠️".yellow(), e);
+            }
+        }
+        SetupCleanup::Background => {
+            if let Err(e) = spawn_background_cleanup(repo, exclude, options) {
+                crate::outln!(
+                    "{} Could not start background cleanup: {}",
+                    "⚠️".yellow(),
+                    e
+                );
+            }
+        }
+        SetupCleanup::Before | SetupCleanup::Off => {}
+    }
+}
+
+/// Creates and prepares a new worktree, undoing completed steps if anything
+/// fails. Returns the backend of a multiplexer session created for `--shell`.
+fn create_worktree(
+    repo: &GitRepo,
+    config: &Config,
+    branch_name: &str,
+    worktree_path: &Path,
+    session_name: &str,
+    options: &SetupOptions,
+    pb: &ProgressBar,
+) -> Result<Option<multiplexer::Backend>> {
+    let hook_context = HookContext {
+        branch: branch_name,
+        worktree: worktree_path,
+        repo_root: &repo.root_dir,
+        base: Some(
+            options
+                .start_point
+                .as_deref()
+                .unwrap_or(&repo.default_branch),
+        ),
+        session: session_name,
+    };
+    let mut transaction = SetupTransaction::new(repo, options.keep_on_failure);
+
+    let result = build_worktree(repo, config, options, &hook_context, pb, &mut transaction);
+    if result.is_err() {
+        pb.abandon();
+        transaction.rollback();
+    }
+    result
+}
+
+/// Creates the branch and worktree and prepares it, recording each completed
+/// step in `transaction` so a failure or Ctrl-C can undo them.
+fn build_worktree(
+    repo: &GitRepo,
+    config: &Config,
+    options: &SetupOptions,
+    hook_context: &HookContext,
+    pb: &ProgressBar,
+    transaction: &mut SetupTransaction,
+) -> Result<Option<multiplexer::Backend>> {
+    let branch_name = hook_context.branch;
+    let worktree_path = hook_context.worktree;
+    hooks::run_hooks(config, HookStage::PreCreate, hook_context)?;
+    check_interrupted()?;
+
+    {
+        let _git = GIT_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+
+        pb.set_message("Checking branch...");
+        let start = ensure_branch_ready(repo, branch_name, options, transaction)?;
+        check_interrupted()?;
+        pb.inc(1);
+
+        pb.set_message("Creating worktree...");
+        crate::outln!("{} Creating git worktree...", "🔧".blue());
+        repo.add_worktree(worktree_path, branch_name)?;
+        transaction.record(SetupStep::AddedWorktree(worktree_path.to_path_buf()));
+        transaction.record(SetupStep::RecordedMetadata {
+            branch: branch_name.to_string(),
+            previous: WorktreeMetadata::load(repo, branch_name).ok().flatten(),
+        });
+        record_metadata(repo, branch_name, start)?;
+        check_interrupted()?;
+        pb.inc(1);
+    }
+
+    pb.set_message("Copying files...");
+    crate::outln!("{} Copying required files...", "📦".blue());
+    file_ops::copy_required_files(&repo.root_dir, worktree_path, config)?;
+    check_interrupted()?;
+    pb.inc(1);
+
+    pb.set_message("Running setup script...");
+    run_setup_scripts(repo, config, branch_name, worktree_path, options, pb)?;
+    check_interrupted()?;
+
+    pb.set_message("Setting up direnv...");
+    file_ops::setup_direnv(worktree_path)?;
+    check_interrupted()?;
+    pb.inc(1);
+
+    hooks::run_hooks(config, HookStage::PostCreate, hook_context)?;
+    check_interrupted()?;
+
+    let created_session = create_shell_session(options, hook_context, transaction)?;
+    check_interrupted()?;
+
+    Ok(created_session)
+}
+
+/// Creates the multiplexer session `--shell` attaches to as the last setup
+/// step, so that a failure or Ctrl-C before setup completes also closes it.
+/// Returns None when the shell will not use a new session.
+fn create_shell_session(
+    options: &SetupOptions,
+    hook_context: &HookContext,
+    transaction: &mut SetupTransaction,
+) -> Result<Option<multiplexer::Backend>> {
+    if !options.start_shell || !options.use_multiplexer || multiplexer::current_backend().is_some()
+    {
+        return Ok(None);
+    }
+    let Some(backend) = multiplexer::preferred_backend() else {
+        return Ok(None);
+    };
+    let session_name = hook_context.session;
+    if multiplexer::session_exists(backend, session_name)? {
+        return Ok(None);
+    }
+
+    crate::outln!(
+        "{} Creating new {} session: {} (dir: {})",
+        "🌀".blue(),
+        backend.display_name(),
+        session_name,
+        hook_context.worktree.display()
+    );
+    multiplexer::create_session(backend, session_name, hook_context.worktree)?;
+    transaction.record(SetupStep::CreatedSession {
+        backend,
+        name: session_name.to_string(),
+    });
+    Ok(Some(backend))
+}
+
+fn check_interrupted() -> Result<()> {
+    if interrupt::interrupted() {
+        bail!("Setup interrupted");
+    }
+    Ok(())
+}
+
+/// A setup step that changed the repository and can be undone.
+enum SetupStep {
+    CreatedBranch(String),
+    AddedWorktree(PathBuf),
+    RecordedMetadata {
+        branch: String,
+        previous: Option<WorktreeMetadata>,
+    },
+    CreatedSession {
+        backend: multiplexer::Backend,
+        name: String,
+    },
+}
+
+/// Completed setup steps, undone in reverse order if setup fails.
+struct SetupTransaction<'a> {
+    repo: &'a GitRepo,
+    steps: Vec<SetupStep>,
+    keep_on_failure: bool,
+}
+
+impl<'a> SetupTransaction<'a> {
+    fn new(repo: &'a GitRepo, keep_on_failure: bool) -> Self {
+        Self {
+            repo,
+            steps: Vec::new(),
+            keep_on_failure,
+        }
+    }
+
+    fn record(&mut self, step: SetupStep) {
+        self.steps.push(step);
+    }
+
+    fn rollback(self) {
+        if self.steps.is_empty() {
+            return;
+        }
+
+        if self.keep_on_failure {
+            crate::outln!(
+                "{} Setup failed; keeping partial results for debugging (--keep-on-failure):",
+                "⚠️".yellow()
             );
-            // Don't fail the entire setup if the script fails
-        } else {
-            crate::outln!("{} Setup script executed successfully", "✨".green());
+            for step in &self.steps {
+                match step {
+                    SetupStep::CreatedBranch(branch) => crate::outln!("  - branch {branch}"),
+                    SetupStep::AddedWorktree(path) => {
+                        crate::outln!("  - worktree {}", path.display())
+                    }
+                    SetupStep::RecordedMetadata { .. } => {}
+                    SetupStep::CreatedSession { backend, name } => {
+                        crate::outln!("  - {} session {name}", backend.display_name())
+                    }
+                }
+            }
+            return;
+        }
+
+        crate::outln!("{} Setup failed; rolling back...", "↩️".yellow());
+        let _git = GIT_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+        for step in self.steps.into_iter().rev() {
+            let (description, result) = match step {
+                SetupStep::CreatedSession { backend, name } => (
+                    format!("Closed {} session {name}", backend.display_name()),
+                    multiplexer::kill_session(backend, &name).map(|_| ()),
+                ),
+                SetupStep::RecordedMetadata { branch, previous } => (
+                    format!("Restored metadata for {branch}"),
+                    match previous {
+                        Some(previous) => previous.save(self.repo),
+                        None => WorktreeMetadata::remove(self.repo, &branch),
+                    },
+                ),
+                SetupStep::AddedWorktree(path) => (
+                    format!("Removed worktree {}", path.display()),
+                    self.repo.remove_worktree(&path, true).map_err(Into::into),
+                ),
+                SetupStep::CreatedBranch(branch) => (
+                    format!("Deleted branch {branch}"),
+                    self.repo.delete_branch(&branch).map_err(Into::into),
+                ),
+            };
+
+            match result {
+                Ok(()) => crate::outln!("  {} {}", "✅".green(), description),
+                Err(e) => crate::outln!("  {} {} failed: {}", "❌".red(), description, e),
+            }
         }
     }
+}
 
+/// Returns the live worktree already checked out on `branch_name`. Stale entries
+/// whose directory was deleted are pruned so the worktree can be recreated.
+fn find_existing_worktree(repo: &GitRepo, branch_name: &str) -> Result<Option<PathBuf>> {
+    let Some(worktree) = repo
+        .list_worktrees()?
+        .into_iter()
+        .find(|worktree| worktree.branch.as_deref() == Some(branch_name))
+    else {
+        return Ok(None);
+    };
+
+    if worktree.is_prunable() {
+        crate::outln!(
+            "{} Pruning stale worktree entry for '{}' (directory {} is missing)",
+            "🧹".yellow(),
+            branch_name,
+            worktree.path.display()
+        );
+        repo.prune_worktrees()?;
+        return Ok(None);
+    }
+
+    Ok(Some(worktree.path))
+}
+
+/// Reuses an existing worktree without touching git state, optionally
+/// refreshing the files workbloom copies in.
+fn refresh_existing_worktree(
+    repo: &GitRepo,
+    config: &Config,
+    branch_name: &str,
+    worktree_path: &Path,
+    options: &SetupOptions,
+) -> Result<()> {
+    let display_worktree_path = display_worktree_path(&repo.root_dir, worktree_path);
+
+    crate::outln!(
+        "{} Worktree for {} already exists: {}",
+        "♻️".green(),
+        branch_name.cyan(),
+        display_worktree_path.display()
+    );
+    if options.start_point.is_some() {
+        crate::outln!(
+            "{} Ignoring --from because the worktree already exists",
+            "⚠️".yellow()
+        );
+    }
+
+    if options.sync {
+        crate::outln!("{} Re-syncing copied files...", "📦".blue());
+        file_ops::copy_required_files(&repo.root_dir, worktree_path, config)?;
+    } else {
+        crate::outln!("    Use --sync to re-copy configured files");
+    }
+    crate::outln!();
     Ok(())
 }
 
-fn run_cleanup_if_exists(repo: &GitRepo, exclude_branch: Option<&str>) -> Result<()> {
+/// Runs the configured setup scripts in order in the new worktree, all logging
+/// to the branch's setup log.
+fn run_setup_scripts(
+    repo: &GitRepo,
+    config: &Config,
+    branch_name: &str,
+    worktree_path: &Path,
+    options: &SetupOptions,
+    pb: &ProgressBar,
+) -> Result<()> {
+    let source_dir = match config.setup_script_source {
+        ScriptSource::Worktree => worktree_path,
+        ScriptSource::Main => repo.root_dir.as_path(),
+    };
+    let log_path = metadata::setup_log_path(repo, branch_name)?;
+    if log_path.exists() {
+        fs::remove_file(&log_path)
+            .with_context(|| format!("Failed to remove {}", log_path.display()))?;
+    }
+
+    for name in config.setup_scripts() {
+        let script_path = source_dir.join(name);
+        if !script_path.is_file() {
+            continue;
+        }
+
+        run_setup_script(
+            name,
+            &script_path,
+            worktree_path,
+            &log_path,
+            config,
+            options,
+            pb,
+        )?;
+        if interrupt::interrupted() {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+fn run_setup_script(
+    name: &str,
+    script_path: &Path,
+    worktree_path: &Path,
+    log_path: &Path,
+    config: &Config,
+    options: &SetupOptions,
+    pb: &ProgressBar,
+) -> Result<()> {
+    // Scripts come from checked-out branch contents, so they only run once the
+    // user has approved their current content
+    if !TrustStore::open().is_trusted(script_path)?
+        && !pb.suspend(|| confirm_untrusted_script(name, script_path))?
+    {
+        return Ok(());
+    }
+
+    crate::outln!("{} Found {}, executing...", "🚀".cyan(), name);
+    crate::outln!("   Log: {}", log_path.display());
+
+    // A directly executed script needs its executable bit, which may have been
+    // lost when it was committed. Only the new worktree's copy is fixed; the
+    // main checkout is left as the user has it
+    #[cfg(unix)]
+    if config.setup_script_interpreter == ScriptInterpreter::Exec {
+        use std::os::unix::fs::PermissionsExt;
+        let mut perms = fs::metadata(script_path)?.permissions();
+        if perms.mode() & 0o100 == 0 {
+            if !script_path.starts_with(worktree_path) {
+                bail!(
+                    "{name} is not executable: {}. Run `chmod +x` on it or set setup_script_interpreter",
+                    script_path.display()
+                );
+            }
+            perms.set_mode(perms.mode() | 0o755);
+            fs::set_permissions(script_path, perms)?;
+        }
+    }
+
+    let mut command = script::script_command(&config.setup_script_interpreter, script_path);
+    command.current_dir(worktree_path);
+    let timeout = options
+        .script_timeout
+        .or(config.setup_script_timeout)
+        .filter(|&secs| secs > 0)
+        .map(Duration::from_secs);
+    let status = script::run_streaming(command, log_path, timeout, pb)
+        .with_context(|| format!("Failed to execute {name}"))?;
+
+    let failure = match status {
+        ScriptStatus::Exited(status) if status.success() => {
+            crate::outln!("{} Setup script executed successfully", "✨".green());
+            return Ok(());
+        }
+        // Reported by the caller's interrupt check
+        ScriptStatus::Interrupted => return Ok(()),
+        ScriptStatus::Exited(status) => match status.code() {
+            Some(code) => format!("exited with status {code}"),
+            None => "was terminated by a signal".to_string(),
+        },
+        ScriptStatus::TimedOut => {
+            format!("timed out after {}s", timeout.unwrap_or_default().as_secs())
+        }
+    };
+
+    if options.strict || config.setup_script_strict {
+        bail!("{name} {failure}. See {}", log_path.display());
+    }
+    // Don't fail the entire setup if the script fails
+    eprintln!(
+        "{} Warning: {} {}. See {}",
+        "⚠️".yellow(),
+        name,
+        failure,
+        log_path.display()
+    );
+    Ok(())
+}
+
+/// Asks whether to allow and run a setup script that is new or changed since it
+/// was last allowed. Without a terminal to ask on, the script is skipped.
+fn confirm_untrusted_script(name: &str, script: &Path) -> Result<bool> {
+    if !io::stdin().is_terminal() {
+        crate::outln!(
+            "{} Skipping {}: it is new or changed since it was last allowed",
+            "⚠️".yellow(),
+            name
+        );
+        crate::outln!(
+            "   Review it, then run `workbloom allow {}`",
+            script.display()
+        );
+        return Ok(false);
+    }
+
+    let _prompt = PROMPT_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+    eprintln!(
+        "{} {} is new or changed since it was last allowed: {}",
+        "⚠️".yellow(),
+        name,
+        script.display()
+    );
+    eprint!("Allow and run it? [y/N]: ");
+    io::stderr().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    if input.trim().to_lowercase() != "y" {
+        crate::outln!("{} Skipping {}", "⏭️".yellow(), name);
+        return Ok(false);
+    }
+
+    TrustStore::open().allow(script)?;
+    Ok(true)
+}
+
+fn run_cleanup_if_exists(repo: &GitRepo, config: &Config, exclude: &[&str]) -> Result<()> {
     crate::outln!(
         "{} Checking for merged branch worktrees to clean up...",
         "🧹".yellow()
     );
 
     // 常に新しい実装を使用（スクリプトは無視）
-    crate::commands::cleanup::cleanup_merged_worktrees_with_exclude(repo, exclude_branch)?;
+    crate::commands::cleanup::cleanup_merged_worktrees_excluding(repo, config, exclude)?;
 
     crate::outln!();
     Ok(())
 }
 
+/// Starts `workbloom cleanup --merged` in a detached process so setup can return
+/// immediately. Its output goes to `.git/workbloom/logs/cleanup.log`.
+fn spawn_background_cleanup(
+    repo: &GitRepo,
+    exclude: &[&str],
+    options: &SetupOptions,
+) -> Result<()> {
+    let log_path = repo
+        .git_common_dir()?
+        .join("workbloom")
+        .join("logs")
+        .join("cleanup.log");
+    if let Some(parent) = log_path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    // Appended to, since a cleanup started by an earlier setup may still be writing
+    let log = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&log_path)
+        .with_context(|| format!("Failed to open {}", log_path.display()))?;
+
+    let mut command = Command::new(env::current_exe()?);
+    command
+        .args(["cleanup", "--merged"])
+        .current_dir(&repo.root_dir)
+        .env("NO_COLOR", "1")
+        .stdin(Stdio::null())
+        .stdout(log.try_clone()?)
+        .stderr(log);
+    for branch in exclude {
+        command.args(["--exclude-branch", branch]);
+    }
+    if let Some(default_branch) = &options.default_branch {
+        command.args(["--default-branch", default_branch]);
+    }
+    // Keep Ctrl-C in the shell that ran setup from reaching the cleanup
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    command
+        .spawn()
+        .context("Failed to start workbloom cleanup")?;
+
+    crate::outln!(
+        "{} Cleaning up merged worktrees in the background (log: {})",
+        "🧹".yellow(),
+        log_path.display()
+    );
+    Ok(())
+}
+
 fn build_progress_bar(print_path: bool) -> ProgressBar {
     if print_path {
         ProgressBar::hidden()
@@ -147,9 +891,38 @@ fn build_progress_bar(print_path: bool) -> ProgressBar {
     }
 }
 
-fn ensure_branch_ready(repo: &GitRepo, branch_name: &str) -> Result<()> {
+/// Records when and by whom the worktree was created. The start point of a
+/// branch created by an earlier setup is kept when the branch is reused.
+fn record_metadata(
+    repo: &GitRepo,
+    branch_name: &str,
+    start: Option<(String, String)>,
+) -> Result<()> {
+    let start = start.or_else(|| {
+        let previous = WorktreeMetadata::load(repo, branch_name).ok().flatten()?;
+        previous.base.zip(previous.base_commit)
+    });
+    WorktreeMetadata::new(repo, branch_name, start).save(repo)
+}
+
+/// Makes sure `branch_name` exists locally. Returns the `(base, base_commit)`
+/// start point when the branch had to be created from scratch.
+fn ensure_branch_ready(
+    repo: &GitRepo,
+    branch_name: &str,
+    options: &SetupOptions,
+    transaction: &mut SetupTransaction,
+) -> Result<Option<(String, String)>> {
     if repo.branch_exists(branch_name)? {
-        return Ok(());
+        if let Some(start_point) = &options.start_point {
+            crate::outln!(
+                "{} Branch '{}' already exists. Ignoring --from {}",
+                "⚠️".yellow(),
+                branch_name,
+                start_point
+            );
+        }
+        return Ok(None);
     }
 
     if repo.remote_branch_exists(branch_name)? {
@@ -158,27 +931,73 @@ fn ensure_branch_ready(repo: &GitRepo, branch_name: &str) -> Result<()> {
             "🌐".blue(),
             branch_name
         );
+        if let Some(start_point) = &options.start_point {
+            crate::outln!(
+                "{} Branch '{}' exists on remote. Ignoring --from {}",
+                "⚠️".yellow(),
+                branch_name,
+                start_point
+            );
+        }
         repo.fetch_remote_branch(branch_name)?;
         repo.create_tracking_branch(branch_name)?;
+        transaction.record(SetupStep::CreatedBranch(branch_name.to_string()));
+        Ok(None)
     } else {
+        let base = options
+            .start_point
+            .clone()
+            .unwrap_or_else(|| repo.default_branch.clone());
+        let start_point = resolve_start_point(repo, &base, options.fetch)?;
+        let base_commit = repo.resolve_commit(&start_point)?;
+
         crate::outln!(
-            "{} Branch '{}' does not exist. Creating it...",
+            "{} Branch '{}' does not exist. Creating it from {}...",
             "📝".yellow(),
-            branch_name
+            branch_name,
+            start_point.cyan()
         );
-        repo.create_branch(branch_name)?;
+        repo.create_branch(branch_name, &base_commit)?;
+        transaction.record(SetupStep::CreatedBranch(branch_name.to_string()));
+        Ok(Some((base, base_commit)))
     }
+}
 
-    Ok(())
+/// Fetches the start point first when `fetch` is set. Branches start from
+/// their freshly fetched `origin/` copy; tags and commits are used as given.
+fn resolve_start_point(repo: &GitRepo, base: &str, fetch: bool) -> Result<String> {
+    if !fetch {
+        return Ok(base.to_string());
+    }
+
+    let remote_branch = base.strip_prefix("origin/").unwrap_or(base);
+    if repo.remote_branch_exists(remote_branch)? {
+        crate::outln!(
+            "{} Fetching '{}' from origin...",
+            "🌐".blue(),
+            remote_branch
+        );
+        repo.fetch_branch(remote_branch)?;
+        return Ok(format!("origin/{remote_branch}"));
+    }
+
+    crate::outln!(
+        "{} Fetching from origin to resolve '{}'...",
+        "🌐".blue(),
+        base
+    );
+    repo.fetch_tags()?;
+    Ok(base.to_string())
 }
 
-fn handle_post_setup(
+pub(crate) fn handle_post_setup(
     print_path: bool,
     start_shell: bool,
     use_multiplexer: bool,
     display_worktree_path: &Path,
     worktree_path: &Path,
     session_name: &str,
+    created_session: Option<multiplexer::Backend>,
 ) -> Result<()> {
     if print_path {
         println!("{}", display_worktree_path.display());
@@ -187,13 +1006,26 @@ fn handle_post_setup(
 
     if start_shell {
         crate::outln!("{} Starting worktree session...", "📂".blue());
-        let inside_backend = multiplexer::current_backend();
-        let started = manage_multiplexer_session(
-            use_multiplexer,
-            inside_backend,
-            worktree_path,
-            session_name,
-        )?;
+        let started = match created_session {
+            Some(backend) => match attach_new_session(backend, session_name) {
+                Ok(()) => true,
+                Err(err) => {
+                    crate::outln!(
+                        "{} {} session failed (falling back to shell): {}",
+                        "⚠️".yellow(),
+                        backend.display_name(),
+                        err
+                    );
+                    false
+                }
+            },
+            None => manage_multiplexer_session(
+                use_multiplexer,
+                multiplexer::current_backend(),
+                worktree_path,
+                session_name,
+            )?,
+        };
 
         if !started {
             crate::outln!("{} Launching shell in worktree directory...", "📂".blue());
@@ -277,7 +1109,25 @@ fn start_multiplexer_session(
         worktree_path.display()
     );
     multiplexer::create_session(backend, session_name, worktree_path)?;
-    multiplexer::attach_session(backend, session_name)
+    attach_new_session(backend, session_name)
+}
+
+/// Attaches to a session this run created, closing it again if that fails.
+fn attach_new_session(backend: multiplexer::Backend, session_name: &str) -> Result<()> {
+    if let Err(e) = multiplexer::attach_session(backend, session_name) {
+        // Don't leave behind a detached session nobody asked for
+        if let Err(kill_err) = multiplexer::kill_session(backend, session_name) {
+            crate::outln!(
+                "{} Could not close {} session {}: {}",
+                "⚠️".yellow(),
+                backend.display_name(),
+                session_name,
+                kill_err
+            );
+        }
+        return Err(e);
+    }
+    Ok(())
 }
 
 fn start_shell_in_directory(worktree_path: &std::path::Path) -> Result<()> {
@@ -291,12 +1141,17 @@ fn start_shell_in_directory(worktree_path: &std::path::Path) -> Result<()> {
     Ok(())
 }
 
-fn display_worktree_path(repo_root: &Path, worktree_dir_name: &str) -> PathBuf {
+/// The worktree path as the user reached the repository: through a symlinked
+/// `$PWD` or the macOS `/private` alias. Worktrees outside the repository are
+/// shown as they are, since `..` on top of a symlink can lead somewhere else.
+pub(crate) fn display_worktree_path(repo_root: &Path, worktree_path: &Path) -> PathBuf {
+    let Ok(relative) = worktree_path.strip_prefix(repo_root) else {
+        return worktree_path.to_path_buf();
+    };
     if let Some(pwd_root) = preferred_pwd_root(repo_root) {
-        return pwd_root.join(worktree_dir_name);
+        return pwd_root.join(relative);
     }
-
-    display_root_alias(repo_root).join(worktree_dir_name)
+    display_root_alias(repo_root).join(relative)
 }
 
 fn preferred_pwd_root(repo_root: &Path) -> Option<PathBuf> {
@@ -451,6 +1306,51 @@ mod tests {
         drop(guard);
     }
 
+    #[test]
+    fn rollback_closes_session_created_for_shell() {
+        // Setup never creates a session from inside a multiplexer
+        if multiplexer::current_backend().is_some() {
+            return;
+        }
+        let options = SetupOptions {
+            start_shell: true,
+            use_multiplexer: true,
+            print_path: false,
+            start_point: None,
+            fetch: false,
+            default_branch: None,
+            sync: false,
+            keep_on_failure: false,
+            script_timeout: None,
+            strict: false,
+            no_cleanup: false,
+        };
+        let context = HookContext {
+            branch: "feature",
+            worktree: Path::new("/tmp/worktree-feature"),
+            repo_root: Path::new("/tmp"),
+            base: None,
+            session: "session-e",
+        };
+        let repo = GitRepo::open(PathBuf::from("/tmp"));
+
+        let mock = Arc::new(MockMultiplexerClient::new(&[Backend::Tmux]));
+        with_mock_multiplexer(mock.clone(), || {
+            let mut transaction = SetupTransaction::new(&repo, false);
+            let created = create_shell_session(&options, &context, &mut transaction).unwrap();
+            assert_eq!(created, Some(Backend::Tmux));
+            assert!(multiplexer::session_exists(Backend::Tmux, "session-e").unwrap());
+
+            transaction.rollback();
+            assert!(!multiplexer::session_exists(Backend::Tmux, "session-e").unwrap());
+        });
+        assert_eq!(
+            mock.created_sessions(),
+            vec![(Backend::Tmux, "session-e".to_string())]
+        );
+        assert!(mock.attached_sessions().is_empty());
+    }
+
     #[test]
     fn manage_multiplexer_session_reattaches_existing_zellij_session() {
         let mock = Arc::new(
@@ -0,0 +1,177 @@
+use anyhow::{bail, Result};
+use colored::*;
+
+use crate::commands::setup::{display_worktree_path, handle_post_setup};
+use crate::config::worktree_dir_name;
+use crate::git::{GitRepo, WorktreeInfo};
+use crate::multiplexer;
+
+pub struct SwitchOptions {
+    pub start_shell: bool,
+    pub use_multiplexer: bool,
+}
+
+/// Resolves `query` to an existing worktree and prints its path or opens it.
+pub fn execute(query: &str, options: &SwitchOptions) -> Result<()> {
+    let repo = GitRepo::new()?;
+    let worktrees = repo.list_worktrees()?;
+    let worktree = find_worktree(&worktrees, query)?;
+
+    if worktree.is_prunable() {
+        bail!(
+            "Worktree for '{}' no longer exists at {}. Run `workbloom cleanup --prune` to remove it",
+            query,
+            worktree.path.display()
+        );
+    }
+
+    let branch = worktree.branch.as_deref().unwrap_or(query);
+    let display_path = display_worktree_path(&repo.root_dir, &worktree.path);
+    let session_name = multiplexer::session_name(&repo.root_dir, &worktree_dir_name(branch));
+
+    crate::outln!(
+        "{} Switching to worktree for {}: {}",
+        "🔀".blue(),
+        branch.cyan(),
+        display_path.display()
+    );
+
+    handle_post_setup(
+        !options.start_shell,
+        options.start_shell,
+        options.use_multiplexer,
+        &display_path,
+        &worktree.path,
+        &session_name,
+        None,
+    )
+}
+
+/// Finds the worktree for `query`, trying in order: exact branch name, exact
+/// directory name, branch prefix, substring and finally fuzzy subsequence
+/// matching. Each stage must produce a single match to succeed.
+fn find_worktree<'a>(worktrees: &'a [WorktreeInfo], query: &str) -> Result<&'a WorktreeInfo> {
+    let candidates: Vec<(&WorktreeInfo, &str)> = worktrees
+        .iter()
+        .filter(|worktree| !worktree.is_bare)
+        .filter_map(|worktree| Some((worktree, worktree.branch.as_deref()?)))
+        .collect();
+
+    for stage in MatchStage::ALL {
+        let matches: Vec<&(&WorktreeInfo, &str)> = candidates
+            .iter()
+            .filter(|(worktree, branch)| stage.matches(worktree, branch, query))
+            .collect();
+
+        match matches.as_slice() {
+            [] => continue,
+            [(worktree, _)] => return Ok(worktree),
+            _ => {
+                let names = matches
+                    .iter()
+                    .map(|(_, branch)| format!("  - {branch}"))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                bail!("'{query}' matches several worktrees:\n{names}");
+            }
+        }
+    }
+
+    bail!("No worktree matches '{query}'. Run `workbloom setup {query}` to create one")
+}
+
+#[derive(Clone, Copy)]
+enum MatchStage {
+    Exact,
+    DirectoryName,
+    Prefix,
+    Substring,
+    Fuzzy,
+}
+
+impl MatchStage {
+    const ALL: [MatchStage; 5] = [
+        MatchStage::Exact,
+        MatchStage::DirectoryName,
+        MatchStage::Prefix,
+        MatchStage::Substring,
+        MatchStage::Fuzzy,
+    ];
+
+    fn matches(self, worktree: &WorktreeInfo, branch: &str, query: &str) -> bool {
+        let branch_lower = branch.to_lowercase();
+        let query_lower = query.to_lowercase();
+        match self {
+            MatchStage::Exact => branch == query,
+            MatchStage::DirectoryName => {
+                worktree.path.file_name().is_some_and(|name| name == query)
+            }
+            MatchStage::Prefix => branch_lower.starts_with(&query_lower),
+            MatchStage::Substring => branch_lower.contains(&query_lower),
+            MatchStage::Fuzzy => is_subsequence(&query_lower, &branch_lower),
+        }
+    }
+}
+
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut haystack = haystack.chars();
+    needle.chars().all(|c| haystack.any(|h| h == c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn worktrees(branches: &[&str]) -> Vec<WorktreeInfo> {
+        let output: String = branches
+            .iter()
+            .map(|branch| {
+                format!(
+                    "worktree /repo/{}\nHEAD 0000\nbranch refs/heads/{}\n\n",
+                    worktree_dir_name(branch),
+                    branch
+                )
+            })
+            .collect();
+        crate::git::parse_worktree_list(&output)
+    }
+
+    fn branch_of(worktree: &WorktreeInfo) -> &str {
+        worktree.branch.as_deref().unwrap()
+    }
+
+    #[test]
+    fn exact_match_wins_over_prefix() -> Result<()> {
+        let worktrees = worktrees(&["feature", "feature/login"]);
+        assert_eq!(branch_of(find_worktree(&worktrees, "feature")?), "feature");
+        Ok(())
+    }
+
+    #[test]
+    fn matches_directory_prefix_and_fuzzy() -> Result<()> {
+        let worktrees = worktrees(&["feature/login", "fix/crash"]);
+        assert_eq!(
+            find_worktree(&worktrees, "worktree-fix-crash")?.path,
+            PathBuf::from("/repo/worktree-fix-crash")
+        );
+        assert_eq!(
+            branch_of(find_worktree(&worktrees, "feat")?),
+            "feature/login"
+        );
+        assert_eq!(branch_of(find_worktree(&worktrees, "CRASH")?), "fix/crash");
+        assert_eq!(
+            branch_of(find_worktree(&worktrees, "flgn")?),
+            "feature/login"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn ambiguous_and_missing_queries_fail() {
+        let worktrees = worktrees(&["feature/login", "feature/logout"]);
+        let err = find_worktree(&worktrees, "feature/log").unwrap_err();
+        assert!(err.to_string().contains("feature/logout"));
+        assert!(find_worktree(&worktrees, "zzz").is_err());
+    }
+}
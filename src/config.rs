@@ -1,13 +1,205 @@
+use colored::*;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::io::{self, BufRead, BufReader};
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
+
+/// Setup script run when no `setup_script` is configured.
+pub const DEFAULT_SETUP_SCRIPT: &str = ".workbloom-setup.sh";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub files_to_copy: Vec<String>,
     pub directories_to_copy: Vec<String>,
+    /// Entries whose line names a strategy other than `copy`, keyed by the
+    /// entry as written (without a trailing `/`).
+    pub copy_strategies: BTreeMap<String, CopyStrategy>,
     pub claude_files: Vec<String>,
+    /// Where new worktrees are placed. Either a base directory (worktrees are
+    /// created as `worktree-<branch>` inside it) or a template containing
+    /// `{branch}` and optionally `{repo}`. Relative paths are resolved against
+    /// the main repository root and `~` is expanded.
+    pub worktree_dir: Option<String>,
+    /// Integration branch to compare against. Detected from `origin/HEAD` when unset.
+    pub default_branch: Option<String>,
+    /// Hours after creation during which cleanup never removes a worktree.
+    pub protect_hours: Option<u64>,
+    /// Scripts run in a new worktree, relative to the checkout they are taken
+    /// from. Empty means `.workbloom-setup.sh`.
+    pub setup_scripts: Vec<String>,
+    /// How setup scripts are started.
+    pub setup_script_interpreter: ScriptInterpreter,
+    /// Which checkout setup scripts are taken from.
+    pub setup_script_source: ScriptSource,
+    /// Seconds after which a setup script is killed. Unset or 0 waits forever.
+    pub setup_script_timeout: Option<u64>,
+    /// Fail (and roll back) setup when a setup script fails or times out.
+    pub setup_script_strict: bool,
+    /// When `setup` cleans up worktrees of merged branches.
+    pub setup_cleanup: SetupCleanup,
+    /// What happens to symlinks found inside copied directories.
+    pub copy_symlinks: SymlinkPolicy,
+    /// `!pattern` lines. Matching paths are left out of copied directories and
+    /// glob matches.
+    pub exclude: Vec<Exclusion>,
+    /// Commands run at worktree lifecycle stages.
+    pub hooks: Hooks,
+    /// Copy entries that were ignored because they could reach outside the
+    /// repository or worktree.
+    pub rejected_entries: Vec<RejectedEntry>,
+    /// Settings whose value could not be used. They keep their default.
+    pub invalid_settings: Vec<InvalidSetting>,
+}
+
+/// A `.workbloom` line naming a path that is not inside the repository.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RejectedEntry {
+    /// 1-based line number in `.workbloom`.
+    pub line: usize,
+    pub entry: String,
+    pub reason: String,
+}
+
+/// A `.workbloom` setting line whose value is not valid for its key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InvalidSetting {
+    /// 1-based line number in `.workbloom`.
+    pub line: usize,
+    pub key: String,
+    pub value: String,
+    pub reason: String,
+}
+
+/// A gitignore-style `!pattern` line. Patterns without a `/` match a name at
+/// any depth; others are relative to the repository root. A trailing `/`
+/// matches directories only.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Exclusion {
+    pattern: String,
+    directory_only: bool,
+}
+
+impl Exclusion {
+    pub fn parse(line: &str) -> Self {
+        let directory_only = line.ends_with('/');
+        let pattern = line.trim_end_matches('/');
+        let pattern = match pattern.strip_prefix('/') {
+            Some(anchored) => anchored.to_string(),
+            None if !pattern.contains('/') => format!("**/{pattern}"),
+            None => pattern.to_string(),
+        };
+        Self {
+            pattern,
+            directory_only,
+        }
+    }
+
+    pub fn matches(&self, relative_path: &str, is_dir: bool) -> bool {
+        (is_dir || !self.directory_only) && entry_matches(&self.pattern, relative_path)
+    }
+}
+
+/// How an entry is brought into a new worktree, chosen by a trailing word on
+/// its `.workbloom` line (`node_modules/ reflink`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CopyStrategy {
+    /// An independent copy.
+    #[default]
+    Copy,
+    /// A symlink to the main repository's copy, so every worktree shares it.
+    Symlink,
+    /// Hard links to the main repository's files. Edits in place are shared;
+    /// tools that replace files break the link.
+    Hardlink,
+    /// Copy-on-write clones on filesystems that support them (btrfs, xfs,
+    /// APFS), falling back to a copy elsewhere.
+    Reflink,
+}
+
+impl CopyStrategy {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "copy" => Some(CopyStrategy::Copy),
+            "symlink" => Some(CopyStrategy::Symlink),
+            "hardlink" => Some(CopyStrategy::Hardlink),
+            "reflink" => Some(CopyStrategy::Reflink),
+            _ => None,
+        }
+    }
+}
+
+/// How symlinks inside a copied directory are treated. Entries named in
+/// `.workbloom` are always resolved.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SymlinkPolicy {
+    /// Recreate the link with the same target.
+    #[default]
+    Preserve,
+    /// Copy what the link points to. Links back into a directory being copied
+    /// are skipped.
+    Follow,
+    /// Leave the link out.
+    Skip,
+}
+
+/// When `setup` runs the merged-worktree cleanup.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SetupCleanup {
+    /// Before creating the new worktree.
+    #[default]
+    Before,
+    /// After the new worktree is ready, before handing it over.
+    After,
+    /// In a detached process once the new worktree is ready.
+    Background,
+    /// Never.
+    Off,
+}
+
+/// How a setup script is started.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScriptInterpreter {
+    /// Run the script directly, letting its shebang pick the interpreter.
+    Exec,
+    /// Run a command with the script path substituted for `{script}`, or
+    /// appended when the command has no placeholder.
+    Command(Vec<String>),
+}
+
+impl Default for ScriptInterpreter {
+    fn default() -> Self {
+        ScriptInterpreter::Command(vec!["bash".to_string()])
+    }
+}
+
+impl ScriptInterpreter {
+    fn parse(value: &str) -> Self {
+        match value {
+            "exec" | "shebang" => ScriptInterpreter::Exec,
+            _ => ScriptInterpreter::Command(value.split_whitespace().map(String::from).collect()),
+        }
+    }
+}
+
+/// Which checkout a setup script is read from. Scripts always run in the new worktree.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScriptSource {
+    /// The copy in the newly checked-out branch.
+    #[default]
+    Worktree,
+    /// The copy in the main repository checkout.
+    Main,
+}
+
+/// Shell commands per lifecycle stage. A stage may be configured several times;
+/// its commands run in file order.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Hooks {
+    pub pre_create: Vec<String>,
+    pub post_create: Vec<String>,
+    pub pre_remove: Vec<String>,
+    pub post_remove: Vec<String>,
 }
 
 impl Default for Config {
@@ -15,10 +207,25 @@ impl Default for Config {
         Self {
             files_to_copy: vec![".envrc".to_string(), ".env".to_string()],
             directories_to_copy: vec![],
+            copy_strategies: BTreeMap::new(),
             claude_files: vec![
                 "settings.json".to_string(),
                 "settings.local.json".to_string(),
             ],
+            worktree_dir: None,
+            default_branch: None,
+            protect_hours: None,
+            setup_scripts: vec![],
+            setup_script_interpreter: ScriptInterpreter::default(),
+            setup_script_source: ScriptSource::default(),
+            setup_script_timeout: None,
+            setup_script_strict: false,
+            setup_cleanup: SetupCleanup::default(),
+            copy_symlinks: SymlinkPolicy::default(),
+            exclude: vec![],
+            hooks: Hooks::default(),
+            rejected_entries: vec![],
+            invalid_settings: vec![],
         }
     }
 }
@@ -32,7 +239,7 @@ impl Config {
             let file = fs::File::open(&workbloom_file)?;
             let reader = BufReader::new(file);
 
-            for line in reader.lines() {
+            for (index, line) in reader.lines().enumerate() {
                 let line = line?;
                 let trimmed = line.trim();
 
@@ -41,17 +248,469 @@ impl Config {
                     continue;
                 }
 
+                // Settings use `key = value`; everything else is a path to copy
+                if let Some((key, value)) = parse_setting(trimmed) {
+                    match config.apply_setting(key, value) {
+                        Ok(true) => continue,
+                        Ok(false) => {}
+                        Err(reason) => {
+                            config.invalid_settings.push(InvalidSetting {
+                                line: index + 1,
+                                key: key.to_string(),
+                                value: value.to_string(),
+                                reason,
+                            });
+                            continue;
+                        }
+                    }
+                }
+
+                if let Some(pattern) = trimmed.strip_prefix('!') {
+                    config.exclude.push(Exclusion::parse(pattern.trim()));
+                    continue;
+                }
+
+                // A trailing word may pick how the entry is copied
+                let (entry, strategy) = match trimmed.rsplit_once(char::is_whitespace) {
+                    Some((entry, word)) => match CopyStrategy::parse(word) {
+                        Some(strategy) => (entry.trim_end(), strategy),
+                        None => (trimmed, CopyStrategy::Copy),
+                    },
+                    None => (trimmed, CopyStrategy::Copy),
+                };
+
+                if let Err(reason) = check_entry(entry) {
+                    config.rejected_entries.push(RejectedEntry {
+                        line: index + 1,
+                        entry: entry.to_string(),
+                        reason: reason.to_string(),
+                    });
+                    continue;
+                }
+
                 // Check if it's a directory (ends with /)
-                if trimmed.ends_with('/') {
-                    config
-                        .directories_to_copy
-                        .push(trimmed.trim_end_matches('/').to_string());
+                let path = entry.trim_end_matches('/').to_string();
+                if strategy != CopyStrategy::Copy {
+                    config.copy_strategies.insert(path.clone(), strategy);
+                }
+                if entry.ends_with('/') {
+                    config.directories_to_copy.push(path);
                 } else {
-                    config.files_to_copy.push(trimmed.to_string());
+                    config.files_to_copy.push(path);
                 }
             }
         }
 
         Ok(config)
     }
+
+    /// Applies a `key = value` line. Returns false when `key` is not a setting,
+    /// in which case the line is a path to copy, and an error when the value
+    /// is not valid for it.
+    fn apply_setting(&mut self, key: &str, value: &str) -> Result<bool, String> {
+        match key {
+            "worktree_dir" => self.worktree_dir = Some(value.to_string()),
+            "default_branch" => self.default_branch = Some(value.to_string()),
+            "protect_hours" => self.protect_hours = Some(parse_number(value, "hours")?),
+            "setup_script" => self.setup_scripts.push(value.to_string()),
+            "setup_script_interpreter" => {
+                self.setup_script_interpreter = ScriptInterpreter::parse(value)
+            }
+            "setup_script_source" => match value {
+                "main" => self.setup_script_source = ScriptSource::Main,
+                "worktree" | "branch" => self.setup_script_source = ScriptSource::Worktree,
+                _ => return Err("expected worktree or main".to_string()),
+            },
+            "setup_script_timeout" => {
+                self.setup_script_timeout = Some(parse_number(value, "seconds")?)
+            }
+            "setup_script_strict" => {
+                self.setup_script_strict = value
+                    .parse()
+                    .map_err(|_| "expected true or false".to_string())?
+            }
+            "setup_cleanup" => match value {
+                "before" => self.setup_cleanup = SetupCleanup::Before,
+                "after" => self.setup_cleanup = SetupCleanup::After,
+                "background" => self.setup_cleanup = SetupCleanup::Background,
+                "off" => self.setup_cleanup = SetupCleanup::Off,
+                _ => return Err("expected before, after, background or off".to_string()),
+            },
+            "copy_symlinks" => match value {
+                "preserve" => self.copy_symlinks = SymlinkPolicy::Preserve,
+                "follow" => self.copy_symlinks = SymlinkPolicy::Follow,
+                "skip" => self.copy_symlinks = SymlinkPolicy::Skip,
+                _ => return Err("expected preserve, follow or skip".to_string()),
+            },
+            "pre_create" => self.hooks.pre_create.push(value.to_string()),
+            "post_create" => self.hooks.post_create.push(value.to_string()),
+            "pre_remove" => self.hooks.pre_remove.push(value.to_string()),
+            "post_remove" => self.hooks.post_remove.push(value.to_string()),
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// Warns about settings that were ignored because of an invalid value.
+    pub fn warn_invalid_settings(&self) {
+        for invalid in &self.invalid_settings {
+            crate::outln!(
+                "{} Warning: ignoring .workbloom line {} ({} = {}): {}",
+                "⚠️".yellow(),
+                invalid.line,
+                invalid.key,
+                invalid.value,
+                invalid.reason
+            );
+        }
+    }
+
+    /// The setup scripts to run, in order.
+    pub fn setup_scripts(&self) -> Vec<&str> {
+        if self.setup_scripts.is_empty() {
+            vec![DEFAULT_SETUP_SCRIPT]
+        } else {
+            self.setup_scripts.iter().map(String::as_str).collect()
+        }
+    }
+
+    /// How the `.workbloom` entry `entry` is copied.
+    pub fn copy_strategy(&self, entry: &str) -> CopyStrategy {
+        self.copy_strategies.get(entry).copied().unwrap_or_default()
+    }
+
+    /// Whether a `!pattern` line excludes `relative_path` from copying.
+    pub fn is_excluded(&self, relative_path: &str, is_dir: bool) -> bool {
+        self.exclude
+            .iter()
+            .any(|exclusion| exclusion.matches(relative_path, is_dir))
+    }
+
+    /// Returns true when a worktree-relative path is one workbloom copies in.
+    pub fn is_copied_path(&self, relative_path: &str) -> bool {
+        let ancestors: Vec<&str> = relative_path
+            .match_indices('/')
+            .map(|(i, _)| &relative_path[..i])
+            .collect();
+        if self.is_excluded(relative_path, false)
+            || ancestors
+                .iter()
+                .any(|ancestor| self.is_excluded(ancestor, true))
+        {
+            return false;
+        }
+
+        self.files_to_copy
+            .iter()
+            .any(|file| entry_matches(file, relative_path))
+            || self.directories_to_copy.iter().any(|dir| {
+                // A symlinked directory is reported as the path itself
+                entry_matches(dir, relative_path)
+                    || ancestors
+                        .iter()
+                        .any(|ancestor| entry_matches(dir, ancestor))
+            })
+            || self
+                .claude_files
+                .iter()
+                .any(|file| relative_path == format!(".claude/{file}"))
+    }
+
+    /// Resolves the directory a worktree for `branch_name` should live in.
+    pub fn worktree_path(&self, repo_root: &Path, branch_name: &str) -> PathBuf {
+        let dir_name = worktree_dir_name(branch_name);
+        let Some(template) = self.worktree_dir.as_deref() else {
+            return repo_root.join(dir_name);
+        };
+
+        let repo_name = repo_root
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("repo");
+        let expanded = template
+            .replace("{repo}", repo_name)
+            .replace("{branch}", &branch_name.replace('/', "-"));
+        let mut path = PathBuf::from(shellexpand::tilde(&expanded).into_owned());
+        if !template.contains("{branch}") {
+            path.push(dir_name);
+        }
+
+        normalize_path(&repo_root.join(path))
+    }
+}
+
+fn parse_setting(line: &str) -> Option<(&str, &str)> {
+    let (key, value) = line.split_once('=')?;
+    let value = value.trim();
+    (!value.is_empty()).then(|| (key.trim(), value))
+}
+
+fn parse_number(value: &str, unit: &str) -> Result<u64, String> {
+    value
+        .parse()
+        .map_err(|_| format!("expected a whole number of {unit}"))
+}
+
+/// Checks that a copy entry names a path inside the repository, so that it can
+/// be joined onto both the main repository and the worktree.
+fn check_entry(entry: &str) -> Result<(), &'static str> {
+    let mut names = 0;
+    for component in Path::new(entry).components() {
+        match component {
+            Component::Prefix(_) | Component::RootDir => {
+                return Err("absolute paths are not allowed")
+            }
+            Component::ParentDir => return Err("'..' is not allowed"),
+            Component::CurDir => {}
+            Component::Normal(_) => names += 1,
+        }
+    }
+    if names == 0 {
+        return Err("the repository root itself cannot be copied");
+    }
+    Ok(())
+}
+
+/// Returns true when a `.workbloom` entry is a glob pattern rather than a literal path.
+pub fn is_glob(entry: &str) -> bool {
+    entry.contains(['*', '?', '['])
+}
+
+/// How `.workbloom` globs match: `*` stays within one path component, `**`
+/// spans directories, and dotfiles are matched like any other name.
+pub const GLOB_OPTIONS: glob::MatchOptions = glob::MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// Whether a `.workbloom` entry names `relative_path`, literally or as a glob.
+pub fn entry_matches(entry: &str, relative_path: &str) -> bool {
+    if !is_glob(entry) {
+        return entry == relative_path;
+    }
+    glob::Pattern::new(entry).is_ok_and(|pattern| pattern.matches_with(relative_path, GLOB_OPTIONS))
+}
+
+/// Directory name used for a branch's worktree in the default layout. Also used
+/// as the multiplexer session identifier so sessions stay stable across layouts.
+pub fn worktree_dir_name(branch_name: &str) -> String {
+    format!("worktree-{}", branch_name.replace('/', "-"))
+}
+
+/// Lexically resolves `.` and `..` components without touching the filesystem.
+pub fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push(component);
+                }
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn default_worktree_path_is_inside_repo() {
+        let config = Config::default();
+        assert_eq!(
+            config.worktree_path(Path::new("/src/app"), "feature/login"),
+            PathBuf::from("/src/app/worktree-feature-login")
+        );
+    }
+
+    #[test]
+    fn worktree_dir_without_placeholder_is_a_base_directory() {
+        let config = Config {
+            worktree_dir: Some("../{repo}.worktrees".to_string()),
+            ..Config::default()
+        };
+        assert_eq!(
+            config.worktree_path(Path::new("/src/app"), "feature/login"),
+            PathBuf::from("/src/app.worktrees/worktree-feature-login")
+        );
+    }
+
+    #[test]
+    fn worktree_dir_template_expands_placeholders() {
+        let config = Config {
+            worktree_dir: Some("/wt/{repo}/{branch}".to_string()),
+            ..Config::default()
+        };
+        assert_eq!(
+            config.worktree_path(Path::new("/src/app"), "feature/login"),
+            PathBuf::from("/wt/app/feature-login")
+        );
+    }
+
+    #[test]
+    fn copied_paths_match_globs() {
+        let config = Config {
+            files_to_copy: vec!["**/.env.local".to_string(), ".env.*".to_string()],
+            directories_to_copy: vec!["apps/*/certs".to_string()],
+            ..Config::default()
+        };
+        assert!(config.is_copied_path(".env.local"));
+        assert!(config.is_copied_path("apps/web/.env.local"));
+        assert!(config.is_copied_path(".env.test"));
+        assert!(!config.is_copied_path("apps/web/.env.test"));
+        assert!(config.is_copied_path("apps/web/certs/dev.pem"));
+        assert!(!config.is_copied_path("apps/web/src/certs.rs"));
+    }
+
+    #[test]
+    fn exclusions_follow_gitignore_rules() {
+        let config = Config {
+            directories_to_copy: vec!["certificates".to_string()],
+            exclude: vec![
+                Exclusion::parse("certificates/tmp/"),
+                Exclusion::parse("*.log"),
+            ],
+            ..Config::default()
+        };
+        assert!(config.is_excluded("certificates/tmp", true));
+        assert!(!config.is_excluded("certificates/tmp", false));
+        assert!(config.is_excluded("debug.log", false));
+        assert!(config.is_excluded("certificates/a/debug.log", false));
+        assert!(config.is_copied_path("certificates"));
+        assert!(config.is_copied_path("certificates/dev.pem"));
+        assert!(!config.is_copied_path("certificates/tmp/cache.bin"));
+        assert!(!config.is_copied_path("certificates/debug.log"));
+    }
+
+    #[test]
+    fn rejects_entries_outside_the_repository() -> io::Result<()> {
+        let temp_dir = TempDir::new()?;
+        fs::write(
+            temp_dir.path().join(".workbloom"),
+            "# secrets\n../../.ssh/id_rsa\n/etc/passwd\nconfig/../../x symlink\n./\n\
+             ./config/app.yml\n**/../*.pem\n",
+        )?;
+
+        let config = Config::load_from_file(temp_dir.path())?;
+        let lines: Vec<_> = config
+            .rejected_entries
+            .iter()
+            .map(|rejected| (rejected.line, rejected.entry.as_str()))
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                (2, "../../.ssh/id_rsa"),
+                (3, "/etc/passwd"),
+                (4, "config/../../x"),
+                (5, "./"),
+                (7, "**/../*.pem")
+            ]
+        );
+        assert!(config.copy_strategies.is_empty());
+        assert_eq!(config.files_to_copy, [".envrc", ".env", "./config/app.yml"]);
+        Ok(())
+    }
+
+    #[test]
+    fn reports_invalid_setting_values() -> io::Result<()> {
+        let temp_dir = TempDir::new()?;
+        fs::write(
+            temp_dir.path().join(".workbloom"),
+            "protect_hours = 2\nprotect_hours = 1d\nsetup_script_timeout = 10m\n\
+             setup_script_strict = yes\nsetup_script_source = mian\nsetup_cleanup = later\n\
+             copy_symlinks = resolve\n",
+        )?;
+
+        let config = Config::load_from_file(temp_dir.path())?;
+        assert_eq!(config.protect_hours, Some(2));
+        assert_eq!(config.setup_script_timeout, None);
+        assert!(!config.setup_script_strict);
+        let invalid: Vec<_> = config
+            .invalid_settings
+            .iter()
+            .map(|invalid| (invalid.line, invalid.key.as_str()))
+            .collect();
+        assert_eq!(
+            invalid,
+            [
+                (2, "protect_hours"),
+                (3, "setup_script_timeout"),
+                (4, "setup_script_strict"),
+                (5, "setup_script_source"),
+                (6, "setup_cleanup"),
+                (7, "copy_symlinks"),
+            ]
+        );
+        assert_eq!(config.invalid_settings[0].value, "1d");
+        assert_eq!(
+            config.invalid_settings[0].reason,
+            "expected a whole number of hours"
+        );
+        assert!(config.files_to_copy.iter().all(|f| !f.contains('=')));
+        Ok(())
+    }
+
+    #[test]
+    fn load_from_file_reads_settings() -> io::Result<()> {
+        let temp_dir = TempDir::new()?;
+        fs::write(
+            temp_dir.path().join(".workbloom"),
+            "worktree_dir = ~/wt/{repo}/{branch}\ndefault_branch = develop\nprotect_hours = 2\n\
+             setup_script = scripts/bootstrap\nsetup_script = Makefile\n\
+             setup_script_interpreter = make -f {script} setup\nsetup_script_source = main\n\
+             setup_script_timeout = 600\nsetup_script_strict = true\n\
+             setup_cleanup = background\ncopy_symlinks = follow\n!certificates/tmp/\n\
+             post_create = make db\npost_create = npm install\nconfig/app.yml\n\
+             .env.shared symlink\nnode_modules/  reflink\nrelease notes.txt\nkey=value.txt\n",
+        )?;
+
+        let config = Config::load_from_file(temp_dir.path())?;
+        assert_eq!(config.worktree_dir.as_deref(), Some("~/wt/{repo}/{branch}"));
+        assert_eq!(config.default_branch.as_deref(), Some("develop"));
+        assert_eq!(config.protect_hours, Some(2));
+        assert_eq!(
+            config.setup_scripts(),
+            vec!["scripts/bootstrap", "Makefile"]
+        );
+        assert_eq!(
+            config.setup_script_interpreter,
+            ScriptInterpreter::Command(vec![
+                "make".into(),
+                "-f".into(),
+                "{script}".into(),
+                "setup".into()
+            ])
+        );
+        assert_eq!(config.setup_script_source, ScriptSource::Main);
+        assert_eq!(config.setup_script_timeout, Some(600));
+        assert!(config.setup_script_strict);
+        assert_eq!(config.setup_cleanup, SetupCleanup::Background);
+        assert_eq!(config.copy_symlinks, SymlinkPolicy::Follow);
+        assert_eq!(config.exclude, vec![Exclusion::parse("certificates/tmp/")]);
+        assert_eq!(config.hooks.post_create, vec!["make db", "npm install"]);
+        assert!(config.files_to_copy.contains(&"config/app.yml".to_string()));
+        assert!(config.files_to_copy.contains(&".env.shared".to_string()));
+        assert!(config
+            .files_to_copy
+            .contains(&"release notes.txt".to_string()));
+        assert!(config.files_to_copy.contains(&"key=value.txt".to_string()));
+        assert!(config
+            .directories_to_copy
+            .contains(&"node_modules".to_string()));
+        assert_eq!(config.copy_strategy(".env.shared"), CopyStrategy::Symlink);
+        assert_eq!(config.copy_strategy("node_modules"), CopyStrategy::Reflink);
+        assert_eq!(config.copy_strategy("config/app.yml"), CopyStrategy::Copy);
+        assert!(!config
+            .files_to_copy
+            .iter()
+            .any(|f| f.contains("worktree_dir")));
+        Ok(())
+    }
 }
@@ -1,18 +1,32 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use colored::*;
 use std::fs;
-use std::path::Path;
+use std::io;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
-use crate::config::Config;
+use crate::config::{is_glob, Config, CopyStrategy, SymlinkPolicy, GLOB_OPTIONS};
+
+/// Directory nesting at which copying gives up, as a guard against runaway recursion.
+const MAX_COPY_DEPTH: usize = 128;
 
 pub fn copy_required_files(main_dir: &Path, worktree_dir: &Path, config: &Config) -> Result<()> {
+    for rejected in &config.rejected_entries {
+        crate::outln!(
+            "{} Warning: ignoring .workbloom line {} ({}): {}",
+            "⚠️".yellow(),
+            rejected.line,
+            rejected.entry,
+            rejected.reason
+        );
+    }
+
     for file in &config.files_to_copy {
-        copy_item(main_dir, worktree_dir, file)?;
+        copy_entry(main_dir, worktree_dir, file, false, config)?;
     }
 
     for dir in &config.directories_to_copy {
-        copy_item(main_dir, worktree_dir, dir)?;
+        copy_entry(main_dir, worktree_dir, dir, true, config)?;
     }
 
     copy_claude_settings(main_dir, worktree_dir, config)?;
@@ -20,7 +34,123 @@ pub fn copy_required_files(main_dir: &Path, worktree_dir: &Path, config: &Config
     Ok(())
 }
 
-fn copy_item(main_dir: &Path, worktree_dir: &Path, item: &str) -> Result<()> {
+/// Copies a `.workbloom` entry, expanding it first when it is a glob pattern.
+fn copy_entry(
+    main_dir: &Path,
+    worktree_dir: &Path,
+    entry: &str,
+    directories: bool,
+    config: &Config,
+) -> Result<()> {
+    let strategy = config.copy_strategy(entry);
+    if !is_glob(entry) {
+        return copy_item(main_dir, worktree_dir, entry, strategy, config);
+    }
+
+    let matches = match expand_glob(main_dir, entry, directories, config) {
+        Ok(matches) => matches,
+        Err(e) => {
+            crate::outln!(
+                "{} Warning: invalid pattern {}: {}",
+                "⚠️".yellow(),
+                entry,
+                e
+            );
+            return Ok(());
+        }
+    };
+    if matches.is_empty() {
+        crate::outln!(
+            "{} Warning: pattern {} matched nothing in main directory",
+            "⚠️".yellow(),
+            entry
+        );
+        return Ok(());
+    }
+
+    crate::outln!(
+        "{} Pattern {} matched: {}",
+        "🔍".blue(),
+        entry,
+        matches.join(", ")
+    );
+    for item in &matches {
+        copy_item(main_dir, worktree_dir, item, strategy, config)?;
+    }
+    Ok(())
+}
+
+/// Finds the paths under `main_dir` matching `pattern`, relative to `main_dir`
+/// and sorted. Only directories match when `directories` is set, and only
+/// other entries otherwise. `.git`, nested checkouts such as other worktrees
+/// and excluded paths are never searched.
+fn expand_glob(
+    main_dir: &Path,
+    pattern: &str,
+    directories: bool,
+    config: &Config,
+) -> Result<Vec<String>> {
+    let matcher = glob::Pattern::new(pattern)?;
+
+    // Start below the literal leading components and, without `**`, stop at the
+    // pattern's depth so that most patterns only read a few directories
+    let components: Vec<&str> = pattern.split('/').collect();
+    let literal_depth = components.iter().take_while(|c| !is_glob(c)).count();
+    let max_depth = if pattern.contains("**") {
+        usize::MAX
+    } else {
+        components.len()
+    };
+
+    let mut matches = Vec::new();
+    let start: PathBuf = components[..literal_depth].iter().collect();
+    let mut pending = vec![(main_dir.join(start), literal_depth)];
+    while let Some((dir, depth)) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries {
+            let entry = entry?;
+            let path = entry.path();
+            if entry.file_name() == ".git" {
+                continue;
+            }
+            let is_dir = entry.file_type()?.is_dir();
+            if is_dir && path.join(".git").exists() {
+                continue;
+            }
+
+            let Ok(relative) = path.strip_prefix(main_dir) else {
+                continue;
+            };
+            let relative = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            if config.is_excluded(&relative, is_dir) {
+                continue;
+            }
+
+            if is_dir == directories && matcher.matches_with(&relative, GLOB_OPTIONS) {
+                matches.push(relative);
+            } else if is_dir && depth + 1 < max_depth {
+                pending.push((path, depth + 1));
+            }
+        }
+    }
+
+    matches.sort();
+    Ok(matches)
+}
+
+fn copy_item(
+    main_dir: &Path,
+    worktree_dir: &Path,
+    item: &str,
+    strategy: CopyStrategy,
+    config: &Config,
+) -> Result<()> {
     let source_path = main_dir.join(item);
     let dest_path = worktree_dir.join(item);
 
@@ -33,22 +163,270 @@ fn copy_item(main_dir: &Path, worktree_dir: &Path, item: &str) -> Result<()> {
         return Ok(());
     }
 
+    // Symlinks along either path could still lead out of the repository or worktree
+    if !stays_inside(main_dir, &source_path)? {
+        crate::outln!(
+            "{} Warning: {} resolves outside the main repository, skipping it",
+            "⚠️".yellow(),
+            item
+        );
+        return Ok(());
+    }
+    // The destination itself is replaced rather than written through, so only
+    // its parent has to stay inside
+    if !stays_inside(worktree_dir, dest_path.parent().unwrap_or(worktree_dir))? {
+        crate::outln!(
+            "{} Warning: {} would be written outside the worktree, skipping it",
+            "⚠️".yellow(),
+            item
+        );
+        return Ok(());
+    }
+
     if let Some(parent) = dest_path.parent() {
         fs::create_dir_all(parent)
             .with_context(|| format!("Failed to create parent directory for {item}"))?;
     }
 
-    if source_path.is_dir() {
-        copy_dir_all(&source_path, &dest_path)?;
-        crate::outln!("{} Copied directory: {}", "📁".green(), item);
+    let is_dir = source_path.is_dir();
+    if !is_dir && !source_path.is_file() {
+        crate::outln!(
+            "{} Warning: {} is not a regular file or directory, skipping it",
+            "⚠️".yellow(),
+            item
+        );
+        return Ok(());
+    }
+
+    let kind = if is_dir { "directory" } else { "file" };
+    if strategy == CopyStrategy::Symlink {
+        if dest_path.is_dir() && !dest_path.is_symlink() {
+            crate::outln!(
+                "{} Warning: {} already exists in worktree, not replacing it with a symlink",
+                "⚠️".yellow(),
+                item
+            );
+            return Ok(());
+        }
+        clear_destination(&dest_path)?;
+        symlink(&source_path, &dest_path, is_dir)
+            .with_context(|| format!("Failed to symlink {item}"))?;
+        crate::outln!("{} Symlinked {}: {}", "🔗".green(), kind, item);
+        return Ok(());
+    }
+
+    let report = if is_dir {
+        // A symlink left by an earlier setup would make the copy write into main
+        remove_symlink(&dest_path)?;
+        copy_dir_all(&source_path, &dest_path, item, strategy, config)
+            .with_context(|| format!("Failed to copy directory {item}"))?
+    } else {
+        let copied_instead = !place_file(&source_path, &dest_path, strategy)
+            .with_context(|| format!("Failed to copy {item}"))?;
+        CopyReport {
+            copied_instead: usize::from(copied_instead),
+            ..CopyReport::default()
+        }
+    };
+
+    let (icon, verb) = match strategy {
+        CopyStrategy::Hardlink => ("🔗", "Hard-linked"),
+        CopyStrategy::Reflink => ("📄", "Cloned"),
+        _ if is_dir => ("📁", "Copied"),
+        _ => ("📄", "Copied"),
+    };
+    let mut notes = Vec::new();
+    if report.excluded > 0 {
+        notes.push(format!("{} excluded", report.excluded));
+    }
+    if report.skipped_links > 0 {
+        notes.push(format!("{} symlinks skipped", report.skipped_links));
+    }
+    if notes.is_empty() {
+        crate::outln!("{} {} {}: {}", icon.green(), verb, kind, item);
     } else {
-        fs::copy(&source_path, &dest_path).with_context(|| format!("Failed to copy {item}"))?;
-        crate::outln!("{} Copied file: {}", "📄".green(), item);
+        crate::outln!(
+            "{} {} {}: {} ({})",
+            icon.green(),
+            verb,
+            kind,
+            item,
+            notes.join(", ")
+        );
+    }
+    if report.copied_instead > 0 {
+        crate::outln!(
+            "{} Warning: {} not available for {}, copied {} file(s) instead",
+            "⚠️".yellow(),
+            if strategy == CopyStrategy::Reflink {
+                "reflink"
+            } else {
+                "hard link"
+            },
+            item,
+            report.copied_instead
+        );
     }
 
     Ok(())
 }
 
+/// Whether `path` stays inside `root` once symlinks are resolved. Components
+/// that don't exist yet can't be symlinks, so the deepest existing ancestor
+/// decides.
+fn stays_inside(root: &Path, path: &Path) -> Result<bool> {
+    let root =
+        fs::canonicalize(root).with_context(|| format!("Failed to resolve {}", root.display()))?;
+    let mut existing = path;
+    while fs::symlink_metadata(existing).is_err() {
+        match existing.parent() {
+            Some(parent) => existing = parent,
+            None => return Ok(false),
+        }
+    }
+    // A broken symlink can't be resolved and is treated as leading outside
+    Ok(fs::canonicalize(existing).is_ok_and(|resolved| resolved.starts_with(&root)))
+}
+
+/// What happened while copying a directory.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct CopyReport {
+    /// Paths skipped because a `!pattern` line excludes them.
+    excluded: usize,
+    /// Files copied because linking or cloning them failed.
+    copied_instead: usize,
+    /// Symlinks left out under `copy_symlinks = skip`.
+    skipped_links: usize,
+}
+
+/// Puts `src` at `dst` using `strategy`, which must not be `Symlink`. Returns
+/// false when a link or clone was not possible and the file was copied instead.
+fn place_file(src: &Path, dst: &Path, strategy: CopyStrategy) -> io::Result<bool> {
+    // `fs::copy` onto an earlier link would write through to the main repository
+    clear_destination(dst)?;
+    let placed = match strategy {
+        CopyStrategy::Hardlink => match fs::hard_link(src, dst) {
+            // A hard link already shares the source's metadata
+            Ok(()) => return Ok(true),
+            Err(e) => Err(e),
+        },
+        CopyStrategy::Reflink => reflink(src, dst),
+        CopyStrategy::Copy | CopyStrategy::Symlink => {
+            fs::copy(src, dst)?;
+            Ok(())
+        }
+    };
+    if placed.is_err() {
+        // `fs::copy` also copies the permission bits
+        fs::copy(src, dst)?;
+    }
+    preserve_mtime(&fs::metadata(src)?, dst);
+    Ok(placed.is_ok())
+}
+
+/// Copies the modification time in `metadata` to `path`. Best effort: build
+/// tools use it to skip work, but a copy with a fresh timestamp is still correct.
+fn preserve_mtime(metadata: &fs::Metadata, path: &Path) {
+    if let (Ok(modified), Ok(file)) = (metadata.modified(), fs::File::open(path)) {
+        let _ = file.set_modified(modified);
+    }
+}
+
+/// Keeps a copied directory writable by its owner, so that a read-only tree
+/// (a Go module cache, vendored 0555 directories) can still be re-synced and
+/// the worktree removed.
+fn owner_writable(mut permissions: fs::Permissions) -> fs::Permissions {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        permissions.set_mode(permissions.mode() | 0o200);
+    }
+    #[cfg(not(unix))]
+    permissions.set_readonly(false);
+    permissions
+}
+
+/// Recreates the symlink `src` at `dst` with the same target.
+fn copy_symlink(src: &Path, dst: &Path) -> io::Result<()> {
+    let target = fs::read_link(src)?;
+    clear_destination(dst)?;
+    symlink(&target, dst, src.is_dir())
+}
+
+/// Removes a file or symlink at `path` so it can be replaced. Directories are kept.
+fn clear_destination(path: &Path) -> io::Result<()> {
+    match fs::symlink_metadata(path) {
+        Ok(metadata) if !metadata.is_dir() => fs::remove_file(path),
+        _ => Ok(()),
+    }
+}
+
+fn remove_symlink(path: &Path) -> io::Result<()> {
+    if path.is_symlink() {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn symlink(src: &Path, dst: &Path, _is_dir: bool) -> io::Result<()> {
+    std::os::unix::fs::symlink(src, dst)
+}
+
+#[cfg(windows)]
+fn symlink(src: &Path, dst: &Path, is_dir: bool) -> io::Result<()> {
+    if is_dir {
+        std::os::windows::fs::symlink_dir(src, dst)
+    } else {
+        std::os::windows::fs::symlink_file(src, dst)
+    }
+}
+
+/// Clones `src` to the new file `dst` with the `FICLONE` ioctl, which shares
+/// extents copy-on-write on btrfs, xfs and similar filesystems.
+#[cfg(target_os = "linux")]
+fn reflink(src: &Path, dst: &Path) -> io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    let source = fs::File::open(src)?;
+    let dest = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(dst)?;
+    // SAFETY: both descriptors stay open for the duration of the call
+    let result = unsafe { libc::ioctl(dest.as_raw_fd(), libc::FICLONE, source.as_raw_fd()) };
+    if result == -1 {
+        let err = io::Error::last_os_error();
+        drop(dest);
+        fs::remove_file(dst)?;
+        return Err(err);
+    }
+    dest.set_permissions(source.metadata()?.permissions())
+}
+
+/// Clones `src` to the new file `dst` with `clonefile`, which APFS supports.
+#[cfg(target_os = "macos")]
+fn reflink(src: &Path, dst: &Path) -> io::Result<()> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let src = CString::new(src.as_os_str().as_bytes())?;
+    let dst = CString::new(dst.as_os_str().as_bytes())?;
+    // SAFETY: both paths are NUL-terminated and outlive the call
+    if unsafe { libc::clonefile(src.as_ptr(), dst.as_ptr(), 0) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn reflink(_src: &Path, _dst: &Path) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "reflink is not supported on this platform",
+    ))
+}
+
 fn copy_claude_settings(main_dir: &Path, worktree_dir: &Path, config: &Config) -> Result<()> {
     let claude_source = main_dir.join(".claude");
     let claude_dest = worktree_dir.join(".claude");
@@ -76,18 +454,104 @@ fn copy_claude_settings(main_dir: &Path, worktree_dir: &Path, config: &Config) -
     Ok(())
 }
 
-fn copy_dir_all(src: &Path, dst: &Path) -> Result<()> {
-    fs::create_dir_all(dst)?;
-    for entry in fs::read_dir(src)? {
-        let entry = entry?;
-        let ty = entry.file_type()?;
-        if ty.is_dir() {
-            copy_dir_all(&entry.path(), &dst.join(entry.file_name()))?;
-        } else {
-            fs::copy(entry.path(), dst.join(entry.file_name()))?;
+/// Recursively copies `src` (at `relative` in the main repository) to `dst`
+/// using `strategy` for each file, skipping paths excluded by `!pattern` lines.
+fn copy_dir_all(
+    src: &Path,
+    dst: &Path,
+    relative: &str,
+    strategy: CopyStrategy,
+    config: &Config,
+) -> Result<CopyReport> {
+    let mut copier = DirCopier {
+        strategy,
+        config,
+        report: CopyReport::default(),
+        ancestors: Vec::new(),
+    };
+    copier.copy_dir(src, dst, relative)?;
+    Ok(copier.report)
+}
+
+struct DirCopier<'a> {
+    strategy: CopyStrategy,
+    config: &'a Config,
+    report: CopyReport,
+    /// Canonical paths of the directories being copied, outermost first, so
+    /// followed symlinks that lead back into one of them are caught.
+    ancestors: Vec<PathBuf>,
+}
+
+impl DirCopier<'_> {
+    fn copy_dir(&mut self, src: &Path, dst: &Path, relative: &str) -> Result<()> {
+        if self.ancestors.len() >= MAX_COPY_DEPTH {
+            bail!("{relative} is nested more than {MAX_COPY_DEPTH} directories deep");
+        }
+        self.ancestors.push(fs::canonicalize(src)?);
+        fs::create_dir_all(dst)?;
+
+        for entry in fs::read_dir(src)? {
+            let entry = entry?;
+            let name = entry.file_name();
+            let entry_relative = format!("{relative}/{}", name.to_string_lossy());
+            let (src_path, dst_path) = (entry.path(), dst.join(&name));
+
+            let mut ty = entry.file_type()?;
+            if ty.is_symlink() && self.config.copy_symlinks == SymlinkPolicy::Follow {
+                match fs::metadata(&src_path) {
+                    Ok(metadata) => ty = metadata.file_type(),
+                    Err(_) => {
+                        crate::outln!(
+                            "{} Warning: skipping broken symlink {}",
+                            "⚠️".yellow(),
+                            entry_relative
+                        );
+                        continue;
+                    }
+                }
+            }
+            if self.config.is_excluded(&entry_relative, ty.is_dir()) {
+                self.report.excluded += 1;
+                continue;
+            }
+
+            if ty.is_symlink() {
+                if self.config.copy_symlinks == SymlinkPolicy::Skip {
+                    self.report.skipped_links += 1;
+                } else {
+                    copy_symlink(&src_path, &dst_path)?;
+                }
+            } else if ty.is_dir() {
+                if self.ancestors.contains(&fs::canonicalize(&src_path)?) {
+                    crate::outln!(
+                        "{} Warning: skipping {}, a symlink back into a directory being copied",
+                        "⚠️".yellow(),
+                        entry_relative
+                    );
+                    continue;
+                }
+                remove_symlink(&dst_path)?;
+                self.copy_dir(&src_path, &dst_path, &entry_relative)?;
+            } else if ty.is_file() {
+                if !place_file(&src_path, &dst_path, self.strategy)? {
+                    self.report.copied_instead += 1;
+                }
+            } else {
+                crate::outln!(
+                    "{} Warning: skipping special file {}",
+                    "⚠️".yellow(),
+                    entry_relative
+                );
+            }
         }
+
+        self.ancestors.pop();
+        // Applied last so that a read-only directory could still be filled
+        let metadata = fs::metadata(src)?;
+        fs::set_permissions(dst, owner_writable(metadata.permissions()))?;
+        preserve_mtime(&metadata, dst);
+        Ok(())
     }
-    Ok(())
 }
 
 pub fn setup_direnv(worktree_dir: &Path) -> Result<()> {
@@ -115,3 +579,250 @@ pub fn setup_direnv(worktree_dir: &Path) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Exclusion;
+    use tempfile::TempDir;
+
+    #[test]
+    fn expands_globs_outside_nested_checkouts() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let root = temp_dir.path();
+        for file in [
+            ".env.local",
+            ".env.test",
+            "apps/web/.env.local",
+            "apps/web/config/app.yml",
+            "apps/api/config/db.yml",
+            "apps/api/config/nested/deep.yml",
+            "worktree-other/.env.local",
+        ] {
+            fs::create_dir_all(root.join(file).parent().unwrap())?;
+            fs::write(root.join(file), "")?;
+        }
+        fs::write(root.join("worktree-other/.git"), "gitdir: elsewhere")?;
+
+        let config = Config::default();
+        assert_eq!(
+            expand_glob(root, "**/.env.local", false, &config)?,
+            [".env.local", "apps/web/.env.local"]
+        );
+        assert_eq!(
+            expand_glob(root, "apps/*/config/*.yml", false, &config)?,
+            ["apps/api/config/db.yml", "apps/web/config/app.yml"]
+        );
+        assert_eq!(
+            expand_glob(root, ".env.*", false, &config)?,
+            [".env.local", ".env.test"]
+        );
+        assert_eq!(
+            expand_glob(root, "apps/*", true, &config)?,
+            ["apps/api", "apps/web"]
+        );
+
+        let config = Config {
+            exclude: vec![Exclusion::parse("apps/api/")],
+            ..Config::default()
+        };
+        assert_eq!(
+            expand_glob(root, "apps/*/config/*.yml", false, &config)?,
+            ["apps/web/config/app.yml"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn directory_copies_skip_exclusions() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let src = temp_dir.path().join("main/certificates");
+        for file in ["dev.pem", "tmp/cache.bin", "sub/debug.log", "sub/ca.pem"] {
+            fs::create_dir_all(src.join(file).parent().unwrap())?;
+            fs::write(src.join(file), "")?;
+        }
+        let config = Config {
+            exclude: vec![
+                Exclusion::parse("certificates/tmp/"),
+                Exclusion::parse("**/*.log"),
+            ],
+            ..Config::default()
+        };
+
+        let dst = temp_dir.path().join("worktree/certificates");
+        let report = copy_dir_all(&src, &dst, "certificates", CopyStrategy::Copy, &config)?;
+        assert_eq!(report.excluded, 2);
+        assert!(dst.join("dev.pem").exists());
+        assert!(dst.join("sub/ca.pem").exists());
+        assert!(!dst.join("tmp").exists());
+        assert!(!dst.join("sub/debug.log").exists());
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn strategies_link_clone_or_copy() -> Result<()> {
+        use std::os::unix::fs::MetadataExt;
+
+        let temp_dir = TempDir::new()?;
+        let main_dir = temp_dir.path().join("main");
+        let worktree = temp_dir.path().join("worktree");
+        fs::create_dir_all(main_dir.join("cache/nested"))?;
+        fs::create_dir_all(&worktree)?;
+        fs::write(main_dir.join(".env"), "SECRET=1\n")?;
+        fs::write(main_dir.join("cache/nested/blob"), "data")?;
+        let config = Config::default();
+
+        copy_item(&main_dir, &worktree, ".env", CopyStrategy::Symlink, &config)?;
+        assert_eq!(fs::read_link(worktree.join(".env"))?, main_dir.join(".env"));
+
+        // Switching back to copying replaces the link instead of writing through it
+        copy_item(&main_dir, &worktree, ".env", CopyStrategy::Copy, &config)?;
+        assert!(!worktree.join(".env").is_symlink());
+        assert_eq!(fs::read_to_string(main_dir.join(".env"))?, "SECRET=1\n");
+
+        copy_item(
+            &main_dir,
+            &worktree,
+            "cache",
+            CopyStrategy::Hardlink,
+            &config,
+        )?;
+        assert_eq!(
+            fs::metadata(worktree.join("cache/nested/blob"))?.ino(),
+            fs::metadata(main_dir.join("cache/nested/blob"))?.ino()
+        );
+
+        // Falls back to a copy on filesystems without copy-on-write support
+        fs::remove_dir_all(worktree.join("cache"))?;
+        copy_item(
+            &main_dir,
+            &worktree,
+            "cache",
+            CopyStrategy::Reflink,
+            &config,
+        )?;
+        assert_eq!(
+            fs::read_to_string(worktree.join("cache/nested/blob"))?,
+            "data"
+        );
+        assert_ne!(
+            fs::metadata(worktree.join("cache/nested/blob"))?.ino(),
+            fs::metadata(main_dir.join("cache/nested/blob"))?.ino()
+        );
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn directory_copies_handle_symlinks_and_special_files() -> Result<()> {
+        use std::ffi::CString;
+        use std::os::unix::ffi::OsStrExt;
+        use std::os::unix::fs::{symlink, PermissionsExt};
+        use std::time::{Duration, SystemTime};
+
+        let temp_dir = TempDir::new()?;
+        let src = temp_dir.path().join("main/tools");
+        fs::create_dir_all(&src)?;
+        fs::write(src.join("run.sh"), "#!/bin/sh\n")?;
+        fs::set_permissions(src.join("run.sh"), fs::Permissions::from_mode(0o755))?;
+        let mtime = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        fs::File::open(src.join("run.sh"))?.set_modified(mtime)?;
+        symlink("run.sh", src.join("latest"))?;
+        symlink(".", src.join("loop"))?;
+        let fifo = CString::new(src.join("pipe").as_os_str().as_bytes())?;
+        // SAFETY: the path is NUL-terminated and outlives the call
+        assert_eq!(unsafe { libc::mkfifo(fifo.as_ptr(), 0o644) }, 0);
+
+        let copy = |policy: SymlinkPolicy, dst: &str| {
+            let config = Config {
+                copy_symlinks: policy,
+                ..Config::default()
+            };
+            let dst = temp_dir.path().join(dst);
+            copy_dir_all(&src, &dst, "tools", CopyStrategy::Copy, &config).map(|r| (dst, r))
+        };
+
+        let (dst, _) = copy(SymlinkPolicy::Preserve, "preserve")?;
+        let script = fs::metadata(dst.join("run.sh"))?;
+        assert_eq!(script.permissions().mode() & 0o777, 0o755);
+        assert_eq!(script.modified()?, mtime);
+        assert_eq!(fs::read_link(dst.join("latest"))?, Path::new("run.sh"));
+        assert_eq!(fs::read_link(dst.join("loop"))?, Path::new("."));
+        assert!(!dst.join("pipe").exists());
+
+        let (dst, _) = copy(SymlinkPolicy::Follow, "follow")?;
+        assert!(!dst.join("latest").is_symlink());
+        assert_eq!(fs::read_to_string(dst.join("latest"))?, "#!/bin/sh\n");
+        assert!(!dst.join("loop").exists());
+
+        let (dst, report) = copy(SymlinkPolicy::Skip, "skip")?;
+        assert_eq!(report.skipped_links, 2);
+        assert!(!dst.join("latest").exists());
+        assert!(dst.join("run.sh").exists());
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn read_only_directories_stay_writable_by_owner() -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new()?;
+        let src = temp_dir.path().join("main/vendor");
+        fs::create_dir_all(src.join("pkg"))?;
+        fs::write(src.join("pkg/mod.go"), "package pkg\n")?;
+        fs::set_permissions(src.join("pkg"), fs::Permissions::from_mode(0o555))?;
+        let dst = temp_dir.path().join("worktree/vendor");
+
+        let config = Config::default();
+        copy_dir_all(&src, &dst, "vendor", CopyStrategy::Copy, &config)?;
+        let mode = fs::metadata(dst.join("pkg"))?.permissions().mode();
+        assert_eq!(mode & 0o777, 0o755);
+
+        // Re-syncing replaces files inside, and the copy can be removed
+        copy_dir_all(&src, &dst, "vendor", CopyStrategy::Copy, &config)?;
+        fs::remove_dir_all(&dst)?;
+
+        fs::set_permissions(src.join("pkg"), fs::Permissions::from_mode(0o755))?;
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn copies_stay_inside_their_roots() -> Result<()> {
+        use std::os::unix::fs::symlink;
+
+        let temp_dir = TempDir::new()?;
+        let main_dir = temp_dir.path().join("main");
+        let worktree = temp_dir.path().join("worktree");
+        let outside = temp_dir.path().join("outside");
+        for dir in [main_dir.join("config"), worktree.clone(), outside.clone()] {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(outside.join("id_rsa"), "secret")?;
+        fs::write(main_dir.join("config/app.yml"), "app")?;
+        symlink(&outside, main_dir.join("ssh"))?;
+        symlink(&outside, worktree.join("config"))?;
+        let config = Config::default();
+
+        copy_item(
+            &main_dir,
+            &worktree,
+            "ssh/id_rsa",
+            CopyStrategy::Copy,
+            &config,
+        )?;
+        assert!(!worktree.join("ssh").exists());
+
+        copy_item(
+            &main_dir,
+            &worktree,
+            "config/app.yml",
+            CopyStrategy::Copy,
+            &config,
+        )?;
+        assert!(!outside.join("app.yml").exists());
+        Ok(())
+    }
+}
@@ -1,15 +1,123 @@
-use anyhow::{bail, Context, Result};
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{Command, Output, Stdio};
+
+pub type GitResult<T> = std::result::Result<T, GitError>;
+
+/// Failures from git commands, classified from git's exit status and stderr.
+#[derive(Debug, thiserror::Error)]
+pub enum GitError {
+    #[error("{0} is not inside a git repository")]
+    NotARepo(PathBuf),
+    #[error("Branch '{0}' already exists")]
+    BranchExists(String),
+    #[error("Branch '{branch}' is already checked out at {path}")]
+    AlreadyCheckedOut { branch: String, path: String },
+    #[error("Remote '{remote}' is unavailable: {stderr}")]
+    RemoteUnavailable { remote: String, stderr: String },
+    #[error("Branch '{0}' does not exist on remote")]
+    RemoteBranchNotFound(String),
+    #[error("'{0}' is not a valid commit")]
+    UnknownRevision(String),
+    #[error("Invalid branch name '{name}': {reason}")]
+    InvalidBranchName { name: String, reason: &'static str },
+    #[error("Failed to run git {args}: {source}")]
+    Spawn {
+        args: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("git {args} failed{}: {stderr}", code.map(|c| format!(" (exit {c})")).unwrap_or_default())]
+    CommandFailed {
+        args: String,
+        code: Option<i32>,
+        stderr: String,
+    },
+    #[error("Unexpected output from git {args}: {output}")]
+    UnexpectedOutput { args: String, output: String },
+}
+
+impl GitError {
+    /// Maps a failed git invocation to the most specific variant its stderr allows.
+    fn from_failure(dir: &Path, args: &[&str], output: &Output) -> Self {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        let lower = stderr.to_lowercase();
+
+        if lower.contains("not a git repository") && !is_remote_command(args) {
+            return GitError::NotARepo(dir.to_path_buf());
+        }
+        if let Some(branch) = quoted_after(&stderr, "a branch named ") {
+            if lower.contains("already exists") {
+                return GitError::BranchExists(branch);
+            }
+        }
+        // Older git says "is already checked out at", newer "is already used by worktree at"
+        for marker in [
+            "is already checked out at ",
+            "is already used by worktree at ",
+        ] {
+            if let Some((before, after)) = stderr.split_once(marker) {
+                let branch = before.rsplit_once(": ").map_or(before, |(_, b)| b);
+                return GitError::AlreadyCheckedOut {
+                    branch: branch.trim().trim_matches('\'').to_string(),
+                    path: after.trim().trim_matches('\'').to_string(),
+                };
+            }
+        }
+        if is_remote_command(args)
+            && [
+                "could not read from remote repository",
+                "unable to access",
+                "does not appear to be a git repository",
+                "permission denied",
+                "could not resolve host",
+            ]
+            .iter()
+            .any(|marker| lower.contains(marker))
+        {
+            return GitError::RemoteUnavailable {
+                remote: "origin".to_string(),
+                stderr,
+            };
+        }
+
+        GitError::CommandFailed {
+            args: args.join(" "),
+            code: output.status.code(),
+            stderr,
+        }
+    }
+}
+
+fn is_remote_command(args: &[&str]) -> bool {
+    matches!(
+        args.first(),
+        Some(&"fetch" | &"ls-remote" | &"push" | &"pull")
+    )
+}
+
+/// Extracts the `'quoted'` word that follows `prefix` in `text`.
+fn quoted_after(text: &str, prefix: &str) -> Option<String> {
+    let rest = text.split_once(prefix)?.1.strip_prefix('\'')?;
+    Some(rest.split_once('\'')?.0.to_string())
+}
 
 pub struct GitRepo {
     pub root_dir: PathBuf,
+    /// Integration branch merged-branch checks compare against.
+    pub default_branch: String,
 }
 
-fn validate_branch_name(branch_name: &str) -> Result<()> {
+fn validate_branch_name(branch_name: &str) -> GitResult<()> {
+    let invalid = |reason| {
+        Err(GitError::InvalidBranchName {
+            name: branch_name.to_string(),
+            reason,
+        })
+    };
+
     // Check for empty branch name
     if branch_name.is_empty() {
-        bail!("Branch name cannot be empty");
+        return invalid("cannot be empty");
     }
 
     // Check for dangerous characters that could lead to command injection
@@ -17,423 +125,690 @@ fn validate_branch_name(branch_name: &str) -> Result<()> {
         '$', '`', '(', ')', '{', '}', '|', '&', ';', '<', '>', '\n', '\r', '\0', '"', '\'', '\\',
     ];
     if branch_name.chars().any(|c| dangerous_chars.contains(&c)) {
-        bail!("Branch name contains invalid characters");
+        return invalid("contains invalid characters");
     }
 
     // Check for valid git branch name patterns
     // Git branch names cannot start/end with dots or slashes
     if branch_name.starts_with('.') || branch_name.ends_with('.') {
-        bail!("Branch name cannot start or end with a dot");
+        return invalid("cannot start or end with a dot");
     }
 
     if branch_name.starts_with('/') || branch_name.ends_with('/') {
-        bail!("Branch name cannot start or end with a slash");
+        return invalid("cannot start or end with a slash");
     }
 
     // Check for consecutive dots
     if branch_name.contains("..") {
-        bail!("Branch name cannot contain consecutive dots");
+        return invalid("cannot contain consecutive dots");
     }
 
     // Check for @{ sequence which has special meaning in git
     if branch_name.contains("@{") {
-        bail!("Branch name cannot contain '@{{' sequence");
+        return invalid("cannot contain '@{' sequence");
     }
 
     Ok(())
 }
 
-impl GitRepo {
-    pub fn new() -> Result<Self> {
-        let root_dir = get_main_repo_dir()?;
-        Ok(Self { root_dir })
+/// Runs git in `dir`. Every git invocation goes through here so that a
+/// non-zero exit is always turned into a `GitError` instead of being ignored.
+fn run_git(dir: &Path, args: &[&str], stdin: Option<Vec<u8>>) -> GitResult<Output> {
+    let output = spawn_git(dir, args, stdin)?;
+    if !output.status.success() {
+        return Err(GitError::from_failure(dir, args, &output));
     }
+    Ok(output)
+}
 
-    pub fn branch_exists(&self, branch_name: &str) -> Result<bool> {
-        validate_branch_name(branch_name)?;
-        let output = Command::new("git")
-            .args([
-                "show-ref",
-                "--verify",
-                "--quiet",
-                &format!("refs/heads/{branch_name}"),
-            ])
-            .current_dir(&self.root_dir)
-            .output()
-            .context("Failed to check if branch exists")?;
+/// Runs a git query whose exit status 1 means "no" (e.g. `show-ref --verify`,
+/// `merge-base --is-ancestor`, `config --get`). Any other failure is an error.
+fn probe_git(dir: &Path, args: &[&str]) -> GitResult<Option<Output>> {
+    let output = spawn_git(dir, args, None)?;
+    match output.status.code() {
+        Some(0) => Ok(Some(output)),
+        Some(1) => Ok(None),
+        _ => Err(GitError::from_failure(dir, args, &output)),
+    }
+}
 
-        Ok(output.status.success())
+fn spawn_git(dir: &Path, args: &[&str], stdin: Option<Vec<u8>>) -> GitResult<Output> {
+    use std::io::Write;
+
+    let spawn_error = |source| GitError::Spawn {
+        args: args.join(" "),
+        source,
+    };
+
+    let mut child = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .stdin(if stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(spawn_error)?;
+
+    // Feed stdin from a thread so a large input cannot deadlock against stdout
+    let writer = match (stdin, child.stdin.take()) {
+        (Some(input), Some(mut pipe)) => Some(std::thread::spawn(move || pipe.write_all(&input))),
+        _ => None,
+    };
+    let output = child.wait_with_output().map_err(spawn_error)?;
+    if let Some(writer) = writer {
+        writer
+            .join()
+            .unwrap_or_else(|_| Err(std::io::Error::other("stdin writer panicked")))
+            .map_err(spawn_error)?;
     }
 
-    pub fn create_branch(&self, branch_name: &str) -> Result<()> {
-        validate_branch_name(branch_name)?;
-        Command::new("git")
-            .args(["checkout", "-b", branch_name])
-            .current_dir(&self.root_dir)
-            .output()
-            .context("Failed to create branch")?;
+    Ok(output)
+}
 
-        Command::new("git")
-            .args(["checkout", "-"])
-            .current_dir(&self.root_dir)
-            .output()
-            .context("Failed to switch back to previous branch")?;
+/// Runs a git command whose progress and hook output the user should see as it
+/// happens. Stderr is echoed line by line through `outln!` and kept so a failure
+/// is still classified; stdout goes to the terminal outside machine output mode.
+fn run_git_streaming(dir: &Path, args: &[&str]) -> GitResult<()> {
+    use std::io::{BufRead, BufReader};
+
+    let spawn_error = |source| GitError::Spawn {
+        args: args.join(" "),
+        source,
+    };
+
+    let mut command = Command::new("git");
+    command.args(args).current_dir(dir).stdin(Stdio::null());
+    crate::output::configure_command_for_machine_output(&mut command);
+    let mut child = command
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(spawn_error)?;
+
+    let mut stderr = Vec::new();
+    if let Some(pipe) = child.stderr.take() {
+        for line in BufReader::new(pipe).split(b'\n') {
+            let line = line.map_err(spawn_error)?;
+            crate::outln!("{}", String::from_utf8_lossy(&line).trim_end());
+            stderr.extend_from_slice(&line);
+            stderr.push(b'\n');
+        }
+    }
+    let status = child.wait().map_err(spawn_error)?;
 
+    if !status.success() {
+        let output = Output {
+            status,
+            stdout: Vec::new(),
+            stderr,
+        };
+        return Err(GitError::from_failure(dir, args, &output));
+    }
+    Ok(())
+}
+
+fn stdout_string(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+impl GitRepo {
+    pub fn new() -> GitResult<Self> {
+        let current_dir = std::env::current_dir().map_err(|source| GitError::Spawn {
+            args: "rev-parse".to_string(),
+            source,
+        })?;
+        Self::discover(&current_dir)
+    }
+
+    /// Opens the main checkout of the repository containing `dir`.
+    pub fn discover(dir: &Path) -> GitResult<Self> {
+        Ok(Self::open(get_main_repo_dir(dir)?))
+    }
+
+    pub fn open(root_dir: PathBuf) -> Self {
+        let mut repo = Self {
+            root_dir,
+            default_branch: String::new(),
+        };
+        repo.default_branch = repo.detect_default_branch();
+        repo
+    }
+
+    /// Overrides the detected default branch, e.g. from config or a CLI flag.
+    pub fn set_default_branch(&mut self, branch_name: Option<String>) -> GitResult<()> {
+        if let Some(branch_name) = branch_name {
+            validate_branch_name(&branch_name)?;
+            self.default_branch = branch_name;
+        }
         Ok(())
     }
 
-    pub fn add_worktree(&self, worktree_path: &Path, branch_name: &str) -> Result<()> {
+    fn git(&self, args: &[&str]) -> GitResult<Output> {
+        run_git(&self.root_dir, args, None)
+    }
+
+    fn git_stdout(&self, args: &[&str]) -> GitResult<String> {
+        Ok(stdout_string(&self.git(args)?))
+    }
+
+    fn probe(&self, args: &[&str]) -> GitResult<Option<Output>> {
+        probe_git(&self.root_dir, args)
+    }
+
+    fn parse_count(args: &[&str], output: &str) -> GitResult<usize> {
+        output
+            .trim()
+            .parse()
+            .map_err(|_| GitError::UnexpectedOutput {
+                args: args.join(" "),
+                output: output.trim().to_string(),
+            })
+    }
+
+    pub fn branch_exists(&self, branch_name: &str) -> GitResult<bool> {
         validate_branch_name(branch_name)?;
-        let mut cmd = Command::new("git");
-        cmd.args([
-            "worktree",
-            "add",
-            worktree_path.to_str().unwrap(),
-            branch_name,
-        ])
-        .current_dir(&self.root_dir);
-        crate::output::configure_command_for_machine_output(&mut cmd)
-            .status()
-            .context("Failed to create worktree")?;
+        let reference = format!("refs/heads/{branch_name}");
+        Ok(self
+            .probe(&["show-ref", "--verify", "--quiet", &reference])?
+            .is_some())
+    }
 
+    /// Creates a branch at `start_point` without touching any worktree's checkout.
+    pub fn create_branch(&self, branch_name: &str, start_point: &str) -> GitResult<()> {
+        validate_branch_name(branch_name)?;
+        validate_branch_name(start_point)?;
+        self.git(&["branch", "--no-track", branch_name, start_point])?;
         Ok(())
     }
 
-    pub fn list_worktrees(&self) -> Result<Vec<WorktreeInfo>> {
-        let output = Command::new("git")
-            .args(["worktree", "list", "--porcelain"])
-            .current_dir(&self.root_dir)
-            .output()
-            .context("Failed to list worktrees")?;
-
-        let output_str = String::from_utf8_lossy(&output.stdout);
-        parse_worktree_list(&output_str)
+    pub fn add_worktree(&self, worktree_path: &Path, branch_name: &str) -> GitResult<()> {
+        validate_branch_name(branch_name)?;
+        let path = worktree_path.to_string_lossy();
+        run_git_streaming(&self.root_dir, &["worktree", "add", &path, branch_name])
     }
 
-    pub fn get_merged_branches(&self) -> Result<Vec<String>> {
-        let output = Command::new("git")
-            .args(["branch", "--merged", "main"])
-            .current_dir(&self.root_dir)
-            .output()
-            .context("Failed to get merged branches")?;
+    pub fn list_worktrees(&self) -> GitResult<Vec<WorktreeInfo>> {
+        let output = self.git_stdout(&["worktree", "list", "--porcelain"])?;
+        Ok(parse_worktree_list(&output))
+    }
 
-        let output_str = String::from_utf8_lossy(&output.stdout);
-        let branches: Vec<String> = output_str
-            .lines()
-            .filter(|line| !line.trim().is_empty())
-            .filter(|line| !line.contains("*"))
-            .filter(|line| !line.trim().eq("main") && !line.trim().eq("master"))
-            .map(|line| line.trim().trim_start_matches("+ ").to_string())
-            .collect();
-        Ok(branches)
+    /// Removes administrative entries for worktrees whose directories are gone.
+    pub fn prune_worktrees(&self) -> GitResult<()> {
+        self.git(&["worktree", "prune"])?;
+        Ok(())
     }
 
-    pub fn remove_worktree(&self, worktree_path: &Path, force: bool) -> Result<()> {
+    pub fn remove_worktree(&self, worktree_path: &Path, force: bool) -> GitResult<()> {
+        let path = worktree_path.to_string_lossy();
         let mut args = vec!["worktree", "remove"];
         if force {
             args.push("--force");
         }
-        args.push(worktree_path.to_str().unwrap());
+        args.push(&path);
 
-        let mut cmd = Command::new("git");
-        cmd.args(&args).current_dir(&self.root_dir);
-        crate::output::configure_command_for_machine_output(&mut cmd)
-            .status()
-            .context("Failed to remove worktree")?;
+        run_git_streaming(&self.root_dir, &args)
+    }
 
+    pub fn delete_branch(&self, branch_name: &str) -> GitResult<()> {
+        validate_branch_name(branch_name)?;
+        self.git(&["branch", "-D", branch_name])?;
         Ok(())
     }
 
-    pub fn delete_branch(&self, branch_name: &str) -> Result<()> {
+    pub fn is_branch_merged(&self, branch_name: &str) -> GitResult<bool> {
+        self.is_branch_merged_into(branch_name, &self.default_branch)
+    }
+
+    pub fn is_branch_merged_into(&self, branch_name: &str, target: &str) -> GitResult<bool> {
         validate_branch_name(branch_name)?;
-        Command::new("git")
-            .args(["branch", "-D", branch_name])
-            .current_dir(&self.root_dir)
-            .output()
-            .context("Failed to delete branch")?;
+        validate_branch_name(target)?;
+        Ok(self
+            .probe(&["merge-base", "--is-ancestor", branch_name, target])?
+            .is_some())
+    }
 
-        Ok(())
+    /// Determines whether `branch_name` has landed in `target` and how: as an
+    /// ancestor (fast-forward or merge commit), as a squash merge (same tree or
+    /// same combined patch as a commit on `target`) or as a rebase merge (every
+    /// commit has a patch-equivalent commit on `target`).
+    pub fn detect_merge(
+        &self,
+        branch_name: &str,
+        target: &str,
+    ) -> GitResult<Option<MergeStrategy>> {
+        if self.is_branch_merged_into(branch_name, target)? {
+            return Ok(Some(MergeStrategy::Ancestor));
+        }
+
+        let Some(merge_base) = self.merge_base(branch_name, target)? else {
+            return Ok(None);
+        };
+
+        let cherry = self.git_stdout(&["cherry", target, branch_name])?;
+        let cherry_lines: Vec<&str> = cherry.lines().filter(|l| !l.is_empty()).collect();
+        if !cherry_lines.is_empty() && cherry_lines.iter().all(|l| l.starts_with('-')) {
+            // A single replayed commit is indistinguishable from a squash merge;
+            // report it as the more common squash
+            return Ok(Some(if cherry_lines.len() == 1 {
+                MergeStrategy::Squash
+            } else {
+                MergeStrategy::Rebase
+            }));
+        }
+
+        let branch_tree = self.git_stdout(&["rev-parse", &format!("{branch_name}^{{tree}}")])?;
+        let range = format!("{merge_base}..{target}");
+        let target_trees = self.git_stdout(&["log", "--format=%T", &range])?;
+        if target_trees.lines().any(|tree| tree == branch_tree.trim()) {
+            return Ok(Some(MergeStrategy::Squash));
+        }
+
+        let branch_diff = self
+            .git(&[
+                "diff",
+                "--no-color",
+                "--no-ext-diff",
+                &merge_base,
+                branch_name,
+            ])?
+            .stdout;
+        let Some(branch_patch_id) = self.patch_ids(branch_diff)?.into_iter().next() else {
+            // The branch changes nothing relative to where it forked
+            return Ok(None);
+        };
+
+        let target_log = self
+            .git(&[
+                "log",
+                "-p",
+                "--no-color",
+                "--no-ext-diff",
+                "--no-merges",
+                &range,
+            ])?
+            .stdout;
+        if self.patch_ids(target_log)?.contains(&branch_patch_id) {
+            return Ok(Some(MergeStrategy::Squash));
+        }
+
+        Ok(None)
     }
 
-    pub fn is_branch_merged(&self, branch_name: &str) -> Result<bool> {
-        validate_branch_name(branch_name)?;
-        let output = Command::new("git")
-            .args(["merge-base", "--is-ancestor", branch_name, "main"])
-            .current_dir(&self.root_dir)
-            .output()
-            .context("Failed to check if branch is merged")?;
+    fn merge_base(&self, branch_name: &str, target: &str) -> GitResult<Option<String>> {
+        // Exit status 1 means the histories share no commit
+        Ok(self
+            .probe(&["merge-base", target, branch_name])?
+            .map(|output| stdout_string(&output).trim().to_string()))
+    }
 
-        Ok(output.status.success())
+    /// Runs `git patch-id --stable` over a patch stream and returns the patch ids.
+    fn patch_ids(&self, patch: Vec<u8>) -> GitResult<Vec<String>> {
+        let output = run_git(&self.root_dir, &["patch-id", "--stable"], Some(patch))?;
+
+        Ok(stdout_string(&output)
+            .lines()
+            .filter_map(|line| line.split_whitespace().next())
+            .map(ToOwned::to_owned)
+            .collect())
     }
 
-    pub fn has_unmerged_commits(&self, branch_name: &str) -> Result<bool> {
+    pub fn has_unmerged_commits(&self, branch_name: &str) -> GitResult<bool> {
         validate_branch_name(branch_name)?;
-        // Check if branch has commits that are not in main
-        let output = Command::new("git")
-            .args(["rev-list", "--count", &format!("main..{branch_name}")])
-            .current_dir(&self.root_dir)
-            .output()
-            .context("Failed to count unmerged commits")?;
-
-        let count_str = String::from_utf8_lossy(&output.stdout);
-        let count = count_str.trim().parse::<i32>().unwrap_or(0);
+        // Check if branch has commits that are not in the default branch
+        let range = format!("{}..{branch_name}", self.default_branch);
+        let args = ["rev-list", "--count", &range];
+        let count = Self::parse_count(&args, &self.git_stdout(&args)?)?;
 
         Ok(count > 0)
     }
 
-    pub fn get_current_branch(&self, worktree_path: &Path) -> Result<String> {
-        let output = Command::new("git")
-            .args(["rev-parse", "--abbrev-ref", "HEAD"])
-            .current_dir(worktree_path)
-            .output()
-            .context("Failed to get current branch")?;
+    /// Returns the branch `origin/HEAD` points to, falling back to a local
+    /// `main` or `master` branch.
+    fn detect_default_branch(&self) -> String {
+        let origin_head = self
+            .probe(&[
+                "symbolic-ref",
+                "--quiet",
+                "--short",
+                "refs/remotes/origin/HEAD",
+            ])
+            .ok()
+            .flatten()
+            .map(|output| stdout_string(&output).trim().to_string());
+
+        if let Some(branch) = origin_head
+            .as_deref()
+            .and_then(|head| head.strip_prefix("origin/"))
+        {
+            return branch.to_string();
+        }
+
+        for candidate in ["main", "master"] {
+            if self.branch_exists(candidate).unwrap_or(false) {
+                return candidate.to_string();
+            }
+        }
 
-        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        "main".to_string()
     }
 
-    pub fn remote_branch_exists(&self, branch_name: &str) -> Result<bool> {
-        validate_branch_name(branch_name)?;
-        // Use ls-remote to check without fetching - much faster
-        let output = Command::new("git")
-            .args(["ls-remote", "--heads", "origin", branch_name])
-            .current_dir(&self.root_dir)
-            .output()
-            .context("Failed to check remote branch")?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            bail!("Failed to check remote branch: {}", stderr);
+    /// Resolves a revision to the full commit hash it points to.
+    pub fn resolve_commit(&self, rev: &str) -> GitResult<String> {
+        validate_branch_name(rev)?;
+        let spec = format!("{rev}^{{commit}}");
+        match self.probe(&["rev-parse", "--verify", "--quiet", &spec])? {
+            Some(output) => Ok(stdout_string(&output).trim().to_string()),
+            None => Err(GitError::UnknownRevision(rev.to_string())),
         }
+    }
+
+    /// Returns true when `name` is a local branch or a remote-tracking branch.
+    pub fn is_branch_ref(&self, name: &str) -> bool {
+        ["refs/heads/", "refs/remotes/"].iter().any(|prefix| {
+            self.probe(&[
+                "show-ref",
+                "--verify",
+                "--quiet",
+                &format!("{prefix}{name}"),
+            ])
+            .is_ok_and(|output| output.is_some())
+        })
+    }
+
+    /// Reads a git config value, returning None when it is unset.
+    pub fn config_value(&self, key: &str) -> Option<String> {
+        let output = self.probe(&["config", "--get", key]).ok()??;
+        let value = stdout_string(&output).trim().to_string();
+        (!value.is_empty()).then_some(value)
+    }
 
-        let output_str = String::from_utf8_lossy(&output.stdout);
-        Ok(!output_str.trim().is_empty())
+    /// Directory shared by all worktrees (`.git` of the main checkout).
+    pub fn git_common_dir(&self) -> GitResult<PathBuf> {
+        let output = self.git_stdout(&["rev-parse", "--git-common-dir"])?;
+        Ok(self.root_dir.join(output.trim()))
     }
 
-    pub fn fetch_remote_branch(&self, branch_name: &str) -> Result<()> {
+    /// Fetches `branch_name` from origin, updating `origin/<branch_name>`.
+    pub fn fetch_branch(&self, branch_name: &str) -> GitResult<()> {
         validate_branch_name(branch_name)?;
-        // Fetch specific remote branch
-        let output = Command::new("git")
-            .args([
-                "fetch",
-                "origin",
-                &format!("refs/heads/{branch_name}:refs/remotes/origin/{branch_name}"),
-            ])
-            .current_dir(&self.root_dir)
-            .output()
-            .context("Failed to execute git fetch command")?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            if stderr.contains("couldn't find remote ref") {
-                bail!("Branch '{}' does not exist on remote", branch_name);
-            } else if stderr.contains("Permission denied") {
-                bail!("Permission denied when fetching from remote");
-            } else {
-                bail!(
-                    "Failed to fetch remote branch '{}': {}",
-                    branch_name,
-                    stderr
-                );
-            }
-        }
+        self.fetch(branch_name, branch_name)
+    }
 
+    /// Fetches origin's branches and tags, so tags and commits that are not
+    /// branch heads can be resolved locally.
+    pub fn fetch_tags(&self) -> GitResult<()> {
+        self.git(&["fetch", "--tags", "origin"])?;
         Ok(())
     }
 
-    pub fn create_tracking_branch(&self, branch_name: &str) -> Result<()> {
+    /// Lists tracked paths with staged or unstaged changes and untracked,
+    /// non-ignored files in a worktree.
+    pub fn worktree_changes(&self, worktree_path: &Path) -> GitResult<WorktreeChanges> {
+        let output = run_git(
+            worktree_path,
+            &["status", "--porcelain=v1", "-z", "--untracked-files=all"],
+            None,
+        )?;
+
+        Ok(parse_status_porcelain(&stdout_string(&output)))
+    }
+
+    /// Counts commits on `branch_name` missing from `base` and commits on `base`
+    /// missing from `branch_name`, as `(ahead, behind)`.
+    pub fn ahead_behind(&self, branch_name: &str, base: &str) -> GitResult<(usize, usize)> {
         validate_branch_name(branch_name)?;
-        // Create local branch tracking remote branch
-        let output = Command::new("git")
-            .args([
-                "checkout",
-                "-b",
-                branch_name,
-                &format!("origin/{branch_name}"),
-            ])
-            .current_dir(&self.root_dir)
-            .output()
-            .context("Failed to execute git checkout command")?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            if stderr.contains("already exists") {
-                bail!("Branch '{}' already exists locally", branch_name);
-            } else if stderr.contains("not a valid object name") {
-                bail!(
-                    "Remote branch 'origin/{}' not found. Did you forget to fetch?",
-                    branch_name
-                );
-            } else {
-                bail!(
-                    "Failed to create tracking branch '{}': {}",
-                    branch_name,
-                    stderr
-                );
-            }
+        validate_branch_name(base)?;
+        let range = format!("{base}...{branch_name}");
+        let args = ["rev-list", "--left-right", "--count", &range];
+        let counts = self.git_stdout(&args)?;
+
+        match counts.split_once('\t') {
+            Some((behind, ahead)) => Ok((
+                Self::parse_count(&args, ahead)?,
+                Self::parse_count(&args, behind)?,
+            )),
+            None => Err(GitError::UnexpectedOutput {
+                args: args.join(" "),
+                output: counts.trim().to_string(),
+            }),
         }
+    }
 
-        // Switch back to the previous branch
-        let switch_output = Command::new("git")
-            .args(["checkout", "-"])
-            .current_dir(&self.root_dir)
-            .output()
-            .context("Failed to execute git checkout - command")?;
-
-        if !switch_output.status.success() {
-            // Log warning but don't fail - the tracking branch was created successfully
-            eprintln!(
-                "Warning: Failed to switch back to previous branch: {}",
-                String::from_utf8_lossy(&switch_output.stderr)
-            );
-        }
+    /// Counts stash entries that were created on `branch_name`.
+    pub fn stash_count(&self, branch_name: &str) -> GitResult<usize> {
+        validate_branch_name(branch_name)?;
+        let output = self.git_stdout(&["stash", "list", "--format=%gs"])?;
 
-        Ok(())
+        let wip_prefix = format!("WIP on {branch_name}:");
+        let on_prefix = format!("On {branch_name}:");
+        Ok(output
+            .lines()
+            .filter(|line| line.starts_with(&wip_prefix) || line.starts_with(&on_prefix))
+            .count())
     }
 
-    pub fn was_branch_merged_to_main(&self, branch_name: &str) -> Result<bool> {
+    /// Counts commits on `branch_name` that exist on no remote and are not part
+    /// of the default branch.
+    pub fn unpushed_commit_count(&self, branch_name: &str) -> GitResult<usize> {
         validate_branch_name(branch_name)?;
-        // First check if branch exists on remote
-        let remote_exists = self.remote_branch_exists(branch_name)?;
-
-        // If branch doesn't exist on remote, it's likely a new branch that shouldn't be cleaned up
-        if !remote_exists {
-            return Ok(false);
-        }
+        let args = [
+            "rev-list",
+            "--count",
+            branch_name,
+            "--not",
+            "--remotes",
+            &self.default_branch,
+        ];
+        Self::parse_count(&args, &self.git_stdout(&args)?)
+    }
 
-        // Get the current HEAD commit of the branch
-        let branch_head_output = Command::new("git")
-            .args(["rev-parse", branch_name])
-            .current_dir(&self.root_dir)
-            .output()
-            .context("Failed to get branch HEAD")?;
+    pub fn get_current_branch(&self, worktree_path: &Path) -> GitResult<String> {
+        let output = run_git(worktree_path, &["rev-parse", "--abbrev-ref", "HEAD"], None)?;
+        Ok(stdout_string(&output).trim().to_string())
+    }
 
-        let branch_head = String::from_utf8_lossy(&branch_head_output.stdout)
-            .trim()
-            .to_string();
+    pub fn remote_branch_exists(&self, branch_name: &str) -> GitResult<bool> {
+        validate_branch_name(branch_name)?;
+        // Use ls-remote to check without fetching - much faster
+        let output = self.git_stdout(&["ls-remote", "--heads", "origin", branch_name])?;
+        Ok(!output.trim().is_empty())
+    }
 
-        // Get the current HEAD commit of main
-        let main_head_output = Command::new("git")
-            .args(["rev-parse", "main"])
-            .current_dir(&self.root_dir)
-            .output()
-            .context("Failed to get main HEAD")?;
+    pub fn fetch_remote_branch(&self, branch_name: &str) -> GitResult<()> {
+        validate_branch_name(branch_name)?;
+        // Fetch specific remote branch
+        self.fetch(
+            branch_name,
+            &format!("refs/heads/{branch_name}:refs/remotes/origin/{branch_name}"),
+        )
+    }
 
-        let main_head = String::from_utf8_lossy(&main_head_output.stdout)
-            .trim()
-            .to_string();
+    fn fetch(&self, branch_name: &str, refspec: &str) -> GitResult<()> {
+        match self.git(&["fetch", "origin", refspec]) {
+            Err(GitError::CommandFailed { stderr, .. })
+                if stderr.contains("couldn't find remote ref") =>
+            {
+                Err(GitError::RemoteBranchNotFound(branch_name.to_string()))
+            }
+            result => result.map(|_| ()),
+        }
+    }
 
-        // If branch points to the same commit as main, it's a new branch with no commits
-        // This should NOT be considered as merged
-        if branch_head == main_head {
-            return Ok(false);
+    pub fn create_tracking_branch(&self, branch_name: &str) -> GitResult<()> {
+        validate_branch_name(branch_name)?;
+        // Create local branch tracking remote branch; `git branch` only writes refs,
+        // so the main worktree's checkout and HEAD reflog stay untouched
+        let upstream = format!("origin/{branch_name}");
+        match self.git(&["branch", "--track", branch_name, &upstream]) {
+            Err(GitError::CommandFailed { stderr, .. })
+                if stderr.contains("not a valid object name") =>
+            {
+                Err(GitError::UnknownRevision(upstream))
+            }
+            result => result.map(|_| ()),
         }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WorktreeChanges {
+    /// Tracked paths with staged or unstaged modifications.
+    pub changed: Vec<String>,
+    /// Untracked paths that are not ignored.
+    pub untracked: Vec<String>,
+}
 
-        // Check if branch has any unique commits
-        // If it has no unique commits but is different from main, it might be behind main
-        let unique_commits_output = Command::new("git")
-            .args(["rev-list", "--count", &format!("main..{branch_name}")])
-            .current_dir(&self.root_dir)
-            .output()
-            .context("Failed to count unique commits")?;
+/// Work that would be lost if a worktree (and its branch) were removed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UnsavedWork {
+    UncommittedChanges(usize),
+    UntrackedFiles(usize),
+    Stashes(usize),
+    UnpushedCommits(usize),
+}
 
-        let unique_count = String::from_utf8_lossy(&unique_commits_output.stdout)
-            .trim()
-            .parse::<i32>()
-            .unwrap_or(0);
-
-        // If branch has no unique commits, check if it's actually been merged
-        if unique_count == 0 {
-            // Check if any merge commit in main has the branch HEAD as a parent
-            let merge_commits_output = Command::new("git")
-                .args(["log", "--merges", "--format=%H %P", "main"])
-                .current_dir(&self.root_dir)
-                .output()
-                .context("Failed to check merge commits")?;
-
-            let merge_commits = String::from_utf8_lossy(&merge_commits_output.stdout);
-
-            // Check if any merge commit has our branch HEAD as a parent
-            for line in merge_commits.lines() {
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if parts.len() >= 2 && parts[1..].contains(&branch_head.as_str()) {
-                    return Ok(true);
-                }
-            }
+impl std::fmt::Display for UnsavedWork {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnsavedWork::UncommittedChanges(n) => write!(f, "{n} uncommitted change(s)"),
+            UnsavedWork::UntrackedFiles(n) => write!(f, "{n} untracked file(s)"),
+            UnsavedWork::Stashes(n) => write!(f, "{n} stash(es)"),
+            UnsavedWork::UnpushedCommits(n) => write!(f, "{n} commit(s) not on any remote"),
         }
+    }
+}
+
+/// How a branch was found to be merged into its target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// The branch head is reachable from the target (fast-forward or merge commit).
+    Ancestor,
+    /// The branch's combined changes were applied as a single commit.
+    Squash,
+    /// Each branch commit was replayed onto the target.
+    Rebase,
+}
 
-        Ok(false)
+impl MergeStrategy {
+    pub fn label(self) -> &'static str {
+        match self {
+            MergeStrategy::Ancestor => "merge",
+            MergeStrategy::Squash => "squash",
+            MergeStrategy::Rebase => "rebase",
+        }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct WorktreeInfo {
     pub path: PathBuf,
+    /// Commit checked out in the worktree; absent for bare repositories.
+    pub head: Option<String>,
     pub branch: Option<String>,
     pub is_detached: bool,
+    pub is_bare: bool,
+    /// Set when the worktree is locked, holding the (possibly empty) reason.
+    pub locked: Option<String>,
+    /// Set when git considers the worktree stale, holding the reason.
+    pub prunable: Option<String>,
 }
 
-fn get_main_repo_dir() -> Result<PathBuf> {
-    let output = Command::new("git")
-        .args(["worktree", "list"])
-        .output()
-        .context("Failed to get worktree list")?;
-
-    if output.status.success() {
-        let output_str = String::from_utf8_lossy(&output.stdout);
-        if let Some(first_line) = output_str.lines().next() {
-            if let Some(path) = first_line.split_whitespace().next() {
-                return Ok(PathBuf::from(path));
-            }
+impl WorktreeInfo {
+    fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            head: None,
+            branch: None,
+            is_detached: false,
+            is_bare: false,
+            locked: None,
+            prunable: None,
         }
     }
 
-    let output = Command::new("git")
-        .args(["rev-parse", "--show-toplevel"])
-        .output()
-        .context("Failed to get git root directory")?;
+    pub fn is_locked(&self) -> bool {
+        self.locked.is_some()
+    }
+
+    pub fn is_prunable(&self) -> bool {
+        self.prunable.is_some()
+    }
+}
+
+fn get_main_repo_dir(dir: &Path) -> GitResult<PathBuf> {
+    // The first porcelain record is always the main worktree
+    let output = run_git(dir, &["worktree", "list", "--porcelain"], None)?;
+    if let Some(worktree) = parse_worktree_list(&stdout_string(&output))
+        .into_iter()
+        .next()
+    {
+        return Ok(worktree.path);
+    }
+
+    let output = run_git(dir, &["rev-parse", "--show-toplevel"], None)?;
+    Ok(PathBuf::from(stdout_string(&output).trim()))
+}
+
+fn parse_status_porcelain(output: &str) -> WorktreeChanges {
+    let mut changes = WorktreeChanges::default();
+    let mut entries = output.split('\0').filter(|entry| !entry.is_empty());
 
-    let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
-    Ok(PathBuf::from(path))
+    while let Some(entry) = entries.next() {
+        let Some((status, path)) = entry.split_at_checked(3) else {
+            continue;
+        };
+        if status.starts_with("??") {
+            changes.untracked.push(path.to_string());
+            continue;
+        }
+        changes.changed.push(path.to_string());
+        // Renames and copies are followed by the original path
+        if status.starts_with('R') || status.starts_with('C') {
+            entries.next();
+        }
+    }
+
+    changes
 }
 
-fn parse_worktree_list(output: &str) -> Result<Vec<WorktreeInfo>> {
+pub(crate) fn parse_worktree_list(output: &str) -> Vec<WorktreeInfo> {
     let mut worktrees = Vec::new();
-    let mut current_path: Option<PathBuf> = None;
-    let mut current_branch: Option<String> = None;
-    let mut is_detached = false;
+    let mut current: Option<WorktreeInfo> = None;
 
+    // Records are separated by blank lines; every record starts with `worktree`
     for line in output.lines() {
-        if line.starts_with("worktree ") {
-            if let Some(path) = current_path.take() {
-                worktrees.push(WorktreeInfo {
-                    path,
-                    branch: current_branch.take(),
-                    is_detached,
-                });
-            }
-            current_path = Some(PathBuf::from(line.trim_start_matches("worktree ")));
-            is_detached = false;
-        } else if line.starts_with("branch refs/heads/") {
-            current_branch = Some(line.trim_start_matches("branch refs/heads/").to_string());
-        } else if line == "detached" {
-            is_detached = true;
+        if let Some(path) = line.strip_prefix("worktree ") {
+            worktrees.extend(current.take());
+            current = Some(WorktreeInfo::new(PathBuf::from(path)));
+            continue;
         }
-    }
+        let Some(worktree) = current.as_mut() else {
+            continue;
+        };
 
-    if let Some(path) = current_path {
-        worktrees.push(WorktreeInfo {
-            path,
-            branch: current_branch,
-            is_detached,
-        });
+        let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+        match key {
+            "HEAD" => worktree.head = Some(value.to_string()),
+            "branch" => worktree.branch = Some(value.trim_start_matches("refs/heads/").to_string()),
+            "detached" => worktree.is_detached = true,
+            "bare" => worktree.is_bare = true,
+            "locked" => worktree.locked = Some(value.to_string()),
+            "prunable" => worktree.prunable = Some(value.to_string()),
+            _ => {}
+        }
     }
 
-    Ok(worktrees)
+    worktrees.extend(current);
+    worktrees
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use anyhow::Result;
     use std::process::Command;
     use tempfile::TempDir;
 
@@ -470,9 +845,7 @@ mod tests {
             .current_dir(repo_path)
             .output()?;
 
-        let repo = GitRepo {
-            root_dir: repo_path.to_path_buf(),
-        };
+        let repo = GitRepo::open(repo_path.to_path_buf());
 
         Ok((temp_dir, repo))
     }
@@ -482,7 +855,7 @@ mod tests {
         let (_temp_dir, repo) = setup_test_repo()?;
 
         // Create a new branch
-        repo.create_branch("test-branch")?;
+        repo.create_branch("test-branch", "main")?;
 
         // A new branch without commits should not have unmerged commits
         assert!(!repo.has_unmerged_commits("test-branch")?);
@@ -501,30 +874,262 @@ mod tests {
         assert!(!repo.branch_exists("non-existent-branch")?);
 
         // Create a branch and check it exists
-        repo.create_branch("test-branch")?;
+        repo.create_branch("test-branch", "main")?;
         assert!(repo.branch_exists("test-branch")?);
 
         Ok(())
     }
 
     #[test]
-    fn test_get_merged_branches() -> Result<()> {
+    fn test_create_branch_from_start_point() -> Result<()> {
+        let (_temp_dir, repo) = setup_test_repo()?;
+        let initial = repo.resolve_commit("main")?;
+
+        Command::new("git")
+            .args(["commit", "--allow-empty", "-m", "Second commit"])
+            .current_dir(&repo.root_dir)
+            .output()?;
+
+        repo.create_branch("from-initial", &initial)?;
+        assert_eq!(repo.resolve_commit("from-initial")?, initial);
+        assert_ne!(repo.resolve_commit("main")?, initial);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_branch_leaves_main_worktree_untouched() -> Result<()> {
         let (_temp_dir, repo) = setup_test_repo()?;
+        std::fs::write(repo.root_dir.join("dirty.txt"), "uncommitted")?;
+        Command::new("git")
+            .args(["add", "dirty.txt"])
+            .current_dir(&repo.root_dir)
+            .output()?;
+        let reflog_before = Command::new("git")
+            .args(["reflog", "HEAD"])
+            .current_dir(&repo.root_dir)
+            .output()?
+            .stdout;
+
+        repo.create_branch("side-branch", "main")?;
+
+        assert!(repo.branch_exists("side-branch")?);
+        assert_eq!(repo.get_current_branch(&repo.root_dir)?, "main");
+        let reflog_after = Command::new("git")
+            .args(["reflog", "HEAD"])
+            .current_dir(&repo.root_dir)
+            .output()?
+            .stdout;
+        assert_eq!(reflog_before, reflog_after);
+
+        Ok(())
+    }
 
-        // Create and immediately check merged branches
-        repo.create_branch("feature-branch")?;
+    fn commit_file(repo: &GitRepo, name: &str, content: &str) -> Result<()> {
+        std::fs::write(repo.root_dir.join(name), content)?;
+        for args in [vec!["add", name], vec!["commit", "-m", name]] {
+            Command::new("git")
+                .args(&args)
+                .current_dir(&repo.root_dir)
+                .output()?;
+        }
+        Ok(())
+    }
 
-        // Switch back to main
+    fn git(repo: &GitRepo, args: &[&str]) -> Result<()> {
         Command::new("git")
-            .args(["checkout", "main"])
+            .args(args)
             .current_dir(&repo.root_dir)
             .output()?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_detect_merge_recognizes_squash_merge() -> Result<()> {
+        let (_temp_dir, repo) = setup_test_repo()?;
+        git(&repo, &["checkout", "-b", "feature"])?;
+        commit_file(&repo, "a.txt", "a")?;
+        commit_file(&repo, "b.txt", "b")?;
+        git(&repo, &["checkout", "main"])?;
+        commit_file(&repo, "other.txt", "other")?;
+
+        assert_eq!(repo.detect_merge("feature", "main")?, None);
+
+        git(&repo, &["merge", "--squash", "feature"])?;
+        git(&repo, &["commit", "-m", "Squashed feature"])?;
+
+        assert_eq!(
+            repo.detect_merge("feature", "main")?,
+            Some(MergeStrategy::Squash)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_detect_merge_recognizes_rebase_merge() -> Result<()> {
+        let (_temp_dir, repo) = setup_test_repo()?;
+        git(&repo, &["checkout", "-b", "feature"])?;
+        commit_file(&repo, "a.txt", "a")?;
+        commit_file(&repo, "b.txt", "b")?;
+        git(&repo, &["checkout", "main"])?;
+        commit_file(&repo, "other.txt", "other")?;
+        git(&repo, &["cherry-pick", "main..feature"])?;
+
+        assert_eq!(
+            repo.detect_merge("feature", "main")?,
+            Some(MergeStrategy::Rebase)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_detect_merge_recognizes_merge_commit() -> Result<()> {
+        let (_temp_dir, repo) = setup_test_repo()?;
+        git(&repo, &["checkout", "-b", "feature"])?;
+        commit_file(&repo, "a.txt", "a")?;
+        git(&repo, &["checkout", "main"])?;
+        git(
+            &repo,
+            &["merge", "--no-ff", "-m", "Merge feature", "feature"],
+        )?;
+
+        assert_eq!(
+            repo.detect_merge("feature", "main")?,
+            Some(MergeStrategy::Ancestor)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_failures_are_typed() -> Result<()> {
+        let (temp_dir, repo) = setup_test_repo()?;
+
+        repo.create_branch("feature", "main")?;
+        assert!(matches!(
+            repo.create_branch("feature", "main"),
+            Err(GitError::BranchExists(branch)) if branch == "feature"
+        ));
+
+        let err = repo
+            .add_worktree(&temp_dir.path().join("worktree-main"), "main")
+            .unwrap_err();
+        assert!(
+            matches!(&err, GitError::AlreadyCheckedOut { branch, .. } if branch == "main"),
+            "{err:?}"
+        );
+
+        assert!(matches!(
+            repo.delete_branch("missing"),
+            Err(GitError::CommandFailed { stderr, .. }) if stderr.contains("missing")
+        ));
+        assert!(matches!(
+            repo.resolve_commit("missing"),
+            Err(GitError::UnknownRevision(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_discover_outside_repo_is_not_a_repo() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        assert!(matches!(
+            GitRepo::discover(temp_dir.path()),
+            Err(GitError::NotARepo(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_worktree_list_reads_full_records() -> Result<()> {
+        let output = "worktree /repo\nbare\n\n\
+            worktree /repo/worktree-a\nHEAD 1111\nbranch refs/heads/feature/a\nlocked on usb drive\n\n\
+            worktree /repo/worktree-b\nHEAD 2222\ndetached\nlocked\n\n\
+            worktree /repo/worktree-c\nHEAD 3333\nbranch refs/heads/c\nprunable gitdir file points to non-existent location\n\n";
+        let worktrees = parse_worktree_list(output);
+
+        assert_eq!(worktrees.len(), 4);
+        assert!(worktrees[0].is_bare);
+        assert_eq!(worktrees[0].head, None);
+
+        assert_eq!(worktrees[1].head.as_deref(), Some("1111"));
+        assert_eq!(worktrees[1].branch.as_deref(), Some("feature/a"));
+        assert_eq!(worktrees[1].locked.as_deref(), Some("on usb drive"));
+        assert!(!worktrees[1].is_prunable());
+
+        assert!(worktrees[2].is_detached);
+        assert_eq!(worktrees[2].locked.as_deref(), Some(""));
+
+        assert_eq!(worktrees[3].branch.as_deref(), Some("c"));
+        assert!(worktrees[3].is_prunable());
+        assert!(!worktrees[3].is_locked());
+        Ok(())
+    }
+
+    #[test]
+    fn test_ahead_behind() -> Result<()> {
+        let (_temp_dir, repo) = setup_test_repo()?;
+        repo.create_branch("feature", "main")?;
+        git(&repo, &["checkout", "-q", "feature"])?;
+        commit_file(&repo, "a.txt", "a")?;
+        commit_file(&repo, "b.txt", "b")?;
+        git(&repo, &["checkout", "-q", "main"])?;
+        commit_file(&repo, "c.txt", "c")?;
+
+        assert_eq!(repo.ahead_behind("feature", "main")?, (2, 1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_status_porcelain() {
+        let output = " M src/lib.rs\0R  new.rs\0old.rs\0?? notes.txt\0A  added.rs\0";
+        let changes = parse_status_porcelain(output);
+        assert_eq!(changes.changed, vec!["src/lib.rs", "new.rs", "added.rs"]);
+        assert_eq!(changes.untracked, vec!["notes.txt"]);
+    }
+
+    #[test]
+    fn test_stash_and_unpushed_counts() -> Result<()> {
+        let (_temp_dir, repo) = setup_test_repo()?;
+        git(&repo, &["checkout", "-b", "feature"])?;
+        commit_file(&repo, "a.txt", "a")?;
+        std::fs::write(repo.root_dir.join("a.txt"), "changed")?;
+        git(&repo, &["stash"])?;
+        git(&repo, &["checkout", "main"])?;
+
+        assert_eq!(repo.stash_count("feature")?, 1);
+        assert_eq!(repo.stash_count("main")?, 0);
+        assert_eq!(repo.unpushed_commit_count("feature")?, 1);
+        assert_eq!(repo.unpushed_commit_count("main")?, 0);
+        Ok(())
+    }
 
-        let merged = repo.get_merged_branches()?;
+    #[test]
+    fn test_default_branch_falls_back_to_local_main() -> Result<()> {
+        let (_temp_dir, repo) = setup_test_repo()?;
+        assert_eq!(repo.default_branch, "main");
+        Ok(())
+    }
 
-        // A branch created from main with no new commits should appear as merged
-        assert!(merged.contains(&"feature-branch".to_string()));
+    #[test]
+    fn test_default_branch_follows_origin_head() -> Result<()> {
+        let (_temp_dir, repo) = setup_test_repo()?;
+        for args in [
+            vec!["branch", "develop"],
+            vec!["update-ref", "refs/remotes/origin/develop", "develop"],
+            vec![
+                "symbolic-ref",
+                "refs/remotes/origin/HEAD",
+                "refs/remotes/origin/develop",
+            ],
+        ] {
+            Command::new("git")
+                .args(&args)
+                .current_dir(&repo.root_dir)
+                .output()?;
+        }
 
+        let repo = GitRepo::open(repo.root_dir.clone());
+        assert_eq!(repo.default_branch, "develop");
         Ok(())
     }
 }
@@ -0,0 +1,139 @@
+use anyhow::{bail, Context, Result};
+use colored::*;
+use std::path::Path;
+use std::process::Command;
+
+use crate::config::Config;
+
+/// Points in a worktree's lifecycle where `.workbloom` can run commands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookStage {
+    /// Before the branch and worktree are created; runs in the repository root.
+    PreCreate,
+    /// After files are copied and the setup script ran; runs in the worktree.
+    PostCreate,
+    /// Before cleanup removes a worktree; runs in the worktree.
+    PreRemove,
+    /// After cleanup removed a worktree; runs in the repository root.
+    PostRemove,
+}
+
+impl HookStage {
+    /// The `.workbloom` setting that configures this stage.
+    pub fn key(self) -> &'static str {
+        match self {
+            HookStage::PreCreate => "pre_create",
+            HookStage::PostCreate => "post_create",
+            HookStage::PreRemove => "pre_remove",
+            HookStage::PostRemove => "post_remove",
+        }
+    }
+
+    fn commands(self, config: &Config) -> &[String] {
+        let hooks = &config.hooks;
+        match self {
+            HookStage::PreCreate => &hooks.pre_create,
+            HookStage::PostCreate => &hooks.post_create,
+            HookStage::PreRemove => &hooks.pre_remove,
+            HookStage::PostRemove => &hooks.post_remove,
+        }
+    }
+}
+
+/// What a hook is told about the worktree, exported as `WORKBLOOM_*` variables.
+pub struct HookContext<'a> {
+    pub branch: &'a str,
+    pub worktree: &'a Path,
+    pub repo_root: &'a Path,
+    pub base: Option<&'a str>,
+    pub session: &'a str,
+}
+
+impl HookContext<'_> {
+    fn env(&self, stage: HookStage) -> Vec<(&'static str, String)> {
+        vec![
+            ("WORKBLOOM_HOOK", stage.key().to_string()),
+            ("WORKBLOOM_BRANCH", self.branch.to_string()),
+            ("WORKBLOOM_WORKTREE", self.worktree.display().to_string()),
+            ("WORKBLOOM_REPO_ROOT", self.repo_root.display().to_string()),
+            ("WORKBLOOM_BASE", self.base.unwrap_or_default().to_string()),
+            ("WORKBLOOM_SESSION", self.session.to_string()),
+        ]
+    }
+
+    /// Directory the hook runs in: the worktree while it exists, else the repository root.
+    fn working_dir(&self, stage: HookStage) -> &Path {
+        match stage {
+            HookStage::PostCreate | HookStage::PreRemove if self.worktree.is_dir() => self.worktree,
+            _ => self.repo_root,
+        }
+    }
+}
+
+/// Runs the commands configured for `stage` in order with `sh -c`, stopping at
+/// the first one that fails.
+pub fn run_hooks(config: &Config, stage: HookStage, context: &HookContext) -> Result<()> {
+    for command in stage.commands(config) {
+        crate::outln!("{} Running {} hook: {}", "🪝".cyan(), stage.key(), command);
+
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c")
+            .arg(command)
+            .current_dir(context.working_dir(stage))
+            .envs(context.env(stage));
+        let status = crate::output::configure_command_for_machine_output(&mut cmd)
+            .status()
+            .with_context(|| format!("Failed to run {} hook: {}", stage.key(), command))?;
+
+        if !status.success() {
+            bail!(
+                "{} hook failed ({}): {}",
+                stage.key(),
+                status.code().map_or_else(
+                    || "terminated by signal".to_string(),
+                    |c| format!("exit {c}")
+                ),
+                command
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn hooks_receive_workbloom_environment() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let worktree = temp_dir.path().join("worktree-feature");
+        std::fs::create_dir(&worktree)?;
+
+        let mut config = Config::default();
+        config.hooks.post_create.push(
+            "echo \"$WORKBLOOM_HOOK $WORKBLOOM_BRANCH $WORKBLOOM_BASE $WORKBLOOM_SESSION\" > hook.out"
+                .to_string(),
+        );
+        let context = HookContext {
+            branch: "feature",
+            worktree: &worktree,
+            repo_root: temp_dir.path(),
+            base: Some("main"),
+            session: "repo-feature",
+        };
+
+        run_hooks(&config, HookStage::PostCreate, &context)?;
+        assert_eq!(
+            std::fs::read_to_string(worktree.join("hook.out"))?,
+            "post_create feature main repo-feature\n"
+        );
+
+        config.hooks.pre_remove.push("exit 3".to_string());
+        let err = run_hooks(&config, HookStage::PreRemove, &context).unwrap_err();
+        assert!(err.to_string().contains("exit 3"));
+        Ok(())
+    }
+}
@@ -0,0 +1,71 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Held by tests that raise SIGINT or depend on no interrupt being recorded.
+#[cfg(test)]
+pub(crate) static TEST_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+/// Returns true once Ctrl-C has been pressed while an `InterruptGuard` was active.
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+/// Records Ctrl-C instead of terminating the process, so a command can undo
+/// partial work before exiting. The previous handler is restored on drop.
+pub struct InterruptGuard {
+    #[cfg(unix)]
+    previous: libc::sighandler_t,
+}
+
+#[cfg(unix)]
+extern "C" fn record_interrupt(_signal: libc::c_int) {
+    INTERRUPTED.store(true, Ordering::SeqCst);
+}
+
+impl InterruptGuard {
+    pub fn install() -> Self {
+        INTERRUPTED.store(false, Ordering::SeqCst);
+
+        #[cfg(unix)]
+        {
+            let handler = record_interrupt as extern "C" fn(libc::c_int);
+            // SAFETY: the handler only stores to an atomic, which is async-signal-safe
+            let previous = unsafe { libc::signal(libc::SIGINT, handler as libc::sighandler_t) };
+            Self { previous }
+        }
+
+        #[cfg(not(unix))]
+        Self {}
+    }
+}
+
+impl Drop for InterruptGuard {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        // SAFETY: restores the handler that was active before `install`
+        unsafe {
+            libc::signal(libc::SIGINT, self.previous);
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sigint_is_recorded_while_guard_is_active() {
+        let _lock = TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let guard = InterruptGuard::install();
+        assert!(!interrupted());
+
+        // SAFETY: raising SIGINT is handled by the guard's handler
+        unsafe {
+            libc::raise(libc::SIGINT);
+        }
+        assert!(interrupted());
+        drop(guard);
+        INTERRUPTED.store(false, Ordering::SeqCst);
+    }
+}
@@ -2,9 +2,14 @@ pub mod commands;
 pub mod config;
 pub mod file_ops;
 pub mod git;
+pub mod hooks;
+pub mod interrupt;
+pub mod metadata;
 pub mod multiplexer;
 pub mod output;
+pub mod script;
 pub mod tmux;
+pub mod trust;
 
 use anyhow::Result;
 
@@ -1,7 +1,8 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
 
-use workbloom::commands::{cleanup, setup};
+use workbloom::commands::{allow, cleanup, list, setup, switch};
 use workbloom::output;
 
 #[derive(Parser)]
@@ -13,6 +14,14 @@ use workbloom::output;
 )]
 #[command(propagate_version = true)]
 struct Cli {
+    #[arg(
+        long,
+        global = true,
+        value_name = "BRANCH",
+        help = "Integration branch to compare against (default: origin/HEAD, then main or master)"
+    )]
+    default_branch: Option<String>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -24,8 +33,11 @@ enum Commands {
         visible_alias = "s"
     )]
     Setup {
-        #[arg(help = "The branch name for the worktree")]
-        branch_name: String,
+        #[arg(
+            required = true,
+            help = "The branch name for the worktree; several branches are set up in parallel"
+        )]
+        branch_names: Vec<String>,
 
         #[arg(long, conflicts_with_all = &["no_shell", "print_path"], help = "Start a new shell in the worktree directory")]
         shell: bool,
@@ -50,6 +62,57 @@ enum Commands {
             help = "Print only the worktree path to stdout (default)"
         )]
         print_path: bool,
+
+        #[arg(
+            long,
+            value_name = "REF",
+            help = "Start point for a newly created branch (default: the repository's default branch)"
+        )]
+        from: Option<String>,
+
+        #[arg(
+            long,
+            help = "Fetch the start point from origin before creating the branch"
+        )]
+        fetch: bool,
+
+        #[arg(
+            long,
+            help = "Re-copy configured files when the worktree already exists"
+        )]
+        sync: bool,
+
+        #[arg(
+            long,
+            help = "Keep the partially created worktree and branch when setup fails, for debugging"
+        )]
+        keep_on_failure: bool,
+
+        #[arg(
+            long,
+            value_name = "SECONDS",
+            help = "Kill the setup script after this many seconds (0 disables the configured timeout)"
+        )]
+        script_timeout: Option<u64>,
+
+        #[arg(
+            long,
+            help = "Fail and roll back setup when the setup script fails or times out"
+        )]
+        strict: bool,
+
+        #[arg(
+            long,
+            help = "Skip cleaning up worktrees of merged branches during setup"
+        )]
+        no_cleanup: bool,
+
+        #[arg(
+            long,
+            conflicts_with_all = &["shell", "no_shell"],
+            help = "Print the result for each branch as JSON"
+        )]
+        json: bool,
     },
 
     #[command(about = "Clean up worktrees", visible_alias = "c")]
@@ -71,6 +134,72 @@ enum Commands {
             help = "Force cleanup without remote branch checks (use with --merged). Still protects recently created worktrees"
         )]
         force: bool,
+
+        #[arg(
+            long,
+            conflicts_with = "status",
+            help = "Show which worktrees, branches and sessions would be removed without changing anything"
+        )]
+        dry_run: bool,
+
+        #[arg(
+            long,
+            help = "Remove worktrees even if they have uncommitted changes, untracked files, stashes or unpushed commits"
+        )]
+        discard_unsaved: bool,
+
+        #[arg(
+            long,
+            value_name = "HOURS",
+            help = "Never remove worktrees created within this many hours (default: 24, 0 disables)"
+        )]
+        protect_hours: Option<u64>,
+
+        #[arg(
+            long,
+            conflicts_with = "status",
+            help = "Prune entries for worktrees whose directories no longer exist"
+        )]
+        prune: bool,
+
+        #[arg(long = "exclude-branch", hide = true, value_name = "BRANCH")]
+        exclude_branches: Vec<String>,
+    },
+
+    #[command(
+        about = "List worktrees with their branch, sync and session state",
+        visible_alias = "ls"
+    )]
+    List {
+        #[arg(long, help = "Print machine-readable JSON instead of a table")]
+        json: bool,
+    },
+
+    #[command(
+        about = "Print the path of an existing worktree or open it in a shell",
+        visible_aliases = ["path", "sw"]
+    )]
+    Switch {
+        #[arg(help = "Branch or worktree directory name; prefix and fuzzy matches are accepted")]
+        query: String,
+
+        #[arg(long, help = "Start a shell or multiplexer session in the worktree")]
+        shell: bool,
+
+        #[arg(
+            long,
+            alias = "no-tmux",
+            help = "Disable terminal multiplexer session management when starting a shell"
+        )]
+        no_mux: bool,
+    },
+
+    #[command(about = "Allow the configured setup scripts to run during setup")]
+    Allow {
+        #[arg(
+            help = "Setup script, or a checkout whose configured setup scripts to allow (default: current directory)"
+        )]
+        path: Option<PathBuf>,
     },
 }
 
@@ -81,16 +210,39 @@ fn main() -> Result<()> {
 
     match cli.command {
         Commands::Setup {
-            branch_name,
+            branch_names,
             shell,
             no_shell,
             no_mux,
             print_path,
+            from,
+            fetch,
+            sync,
+            keep_on_failure,
+            script_timeout,
+            strict,
+            no_cleanup,
+            json,
         } => {
-            let start_shell = shell;
-            let print_path = print_path || (!shell && !no_shell);
+            let print_path = print_path || json || (!shell && !no_shell);
             output::set_machine_output(print_path);
-            setup::execute(&branch_name, start_shell, !no_mux, print_path)?;
+            let options = setup::SetupOptions {
+                start_shell: shell,
+                use_multiplexer: !no_mux,
+                print_path,
+                start_point: from,
+                fetch,
+                default_branch: cli.default_branch,
+                sync,
+                keep_on_failure,
+                script_timeout,
+                strict,
+                no_cleanup,
+            };
+            match branch_names.as_slice() {
+                [branch_name] if !json => setup::execute(branch_name, &options)?,
+                _ => setup::execute_batch(&branch_names, &options, json)?,
+            }
         }
         Commands::Cleanup {
             merged,
@@ -98,6 +250,11 @@ fn main() -> Result<()> {
             interactive,
             status,
             force,
+            dry_run,
+            discard_unsaved,
+            protect_hours,
+            prune,
+            exclude_branches,
         } => {
             let mode = if merged || (pattern.is_none() && !interactive && !status) {
                 cleanup::CleanupMode::Merged { force }
@@ -109,7 +266,39 @@ fn main() -> Result<()> {
                 cleanup::CleanupMode::Status
             };
 
-            cleanup::execute(mode)?;
+            let options = cleanup::CleanupOptions {
+                default_branch: cli.default_branch,
+                dry_run,
+                discard_unsaved,
+                protect_hours,
+                prune,
+                exclude_branches,
+                ..Default::default()
+            };
+            cleanup::execute(mode, &options)?;
+        }
+        Commands::List { json } => {
+            output::set_machine_output(json);
+            let options = list::ListOptions {
+                default_branch: cli.default_branch,
+                json,
+            };
+            list::execute(&options)?;
+        }
+        Commands::Switch {
+            query,
+            shell,
+            no_mux,
+        } => {
+            output::set_machine_output(!shell);
+            let options = switch::SwitchOptions {
+                start_shell: shell,
+                use_multiplexer: !no_mux,
+            };
+            switch::execute(&query, &options)?;
+        }
+        Commands::Allow { path } => {
+            allow::execute(path.as_deref())?;
         }
     }
 
@@ -0,0 +1,162 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::git::GitRepo;
+
+/// Information workbloom records about a worktree it created, stored under
+/// `.git/workbloom/worktrees/<branch>.json` so it is shared by all worktrees.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WorktreeMetadata {
+    pub branch: String,
+    /// The ref the branch was started from, as given by the user. Only known
+    /// when workbloom created the branch.
+    #[serde(default)]
+    pub base: Option<String>,
+    /// The commit `base` resolved to when the branch was created.
+    #[serde(default)]
+    pub base_commit: Option<String>,
+    /// When the worktree was created, in seconds since the Unix epoch.
+    #[serde(default)]
+    pub created_at: u64,
+    /// Who created the worktree (`user.name`, falling back to `$USER`).
+    #[serde(default)]
+    pub creator: String,
+}
+
+impl WorktreeMetadata {
+    /// Metadata for a worktree created now. `start` is the `(base, base_commit)`
+    /// pair when the branch itself was created as well.
+    pub fn new(repo: &GitRepo, branch_name: &str, start: Option<(String, String)>) -> Self {
+        let (base, base_commit) = start.unzip();
+        let created_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let creator = repo
+            .config_value("user.name")
+            .or_else(|| std::env::var("USER").ok())
+            .unwrap_or_default();
+
+        Self {
+            branch: branch_name.to_string(),
+            base,
+            base_commit,
+            created_at,
+            creator,
+        }
+    }
+
+    /// Time since the worktree was created, if it was recorded.
+    pub fn age(&self) -> Option<Duration> {
+        if self.created_at == 0 {
+            return None;
+        }
+        let created = UNIX_EPOCH + Duration::from_secs(self.created_at);
+        SystemTime::now().duration_since(created).ok()
+    }
+
+    pub fn load(repo: &GitRepo, branch_name: &str) -> Result<Option<Self>> {
+        let path = metadata_path(repo, branch_name)?;
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let metadata = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        Ok(Some(metadata))
+    }
+
+    pub fn save(&self, repo: &GitRepo) -> Result<()> {
+        let path = metadata_path(repo, &self.branch)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(&path, content).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    pub fn remove(repo: &GitRepo, branch_name: &str) -> Result<()> {
+        let path = metadata_path(repo, branch_name)?;
+        if path.exists() {
+            fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove {}", path.display()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Age of a worktree. Uses the recorded creation time; worktrees created before
+/// metadata was recorded fall back to the mtime of their `.git` file, which git
+/// writes once when the worktree is added.
+pub fn worktree_age(repo: &GitRepo, worktree_path: &Path, branch_name: &str) -> Option<Duration> {
+    let recorded = WorktreeMetadata::load(repo, branch_name)
+        .ok()
+        .flatten()
+        .and_then(|metadata| metadata.age());
+    recorded.or_else(|| {
+        let modified = fs::metadata(worktree_path.join(".git"))
+            .and_then(|m| m.modified())
+            .ok()?;
+        SystemTime::now().duration_since(modified).ok()
+    })
+}
+
+/// Where the output of the last setup script run for `branch_name` is kept.
+/// Kept apart from `logs/cleanup.log` so that no branch name can collide with it.
+pub fn setup_log_path(repo: &GitRepo, branch_name: &str) -> Result<PathBuf> {
+    Ok(repo
+        .git_common_dir()?
+        .join("workbloom")
+        .join("logs")
+        .join("setup")
+        .join(format!("{branch_name}.log")))
+}
+
+fn metadata_path(repo: &GitRepo, branch_name: &str) -> Result<PathBuf> {
+    Ok(repo
+        .git_common_dir()?
+        .join("workbloom")
+        .join("worktrees")
+        .join(format!("{branch_name}.json")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    #[test]
+    fn save_and_load_round_trip() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        Command::new("git")
+            .args(["init"])
+            .current_dir(temp_dir.path())
+            .output()?;
+        let repo = GitRepo::open(temp_dir.path().to_path_buf());
+
+        let metadata = WorktreeMetadata::new(
+            &repo,
+            "feature/login",
+            Some(("origin/main".to_string(), "0123456789abcdef".to_string())),
+        );
+        metadata.save(&repo)?;
+        assert!(metadata.age().is_some());
+
+        assert_eq!(
+            WorktreeMetadata::load(&repo, "feature/login")?,
+            Some(metadata)
+        );
+
+        WorktreeMetadata::remove(&repo, "feature/login")?;
+        assert_eq!(WorktreeMetadata::load(&repo, "feature/login")?, None);
+        Ok(())
+    }
+}
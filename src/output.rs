@@ -1,8 +1,13 @@
+use std::cell::RefCell;
 use std::process::{Command, Stdio};
 use std::sync::atomic::{AtomicBool, Ordering};
 
 static MACHINE_OUTPUT: AtomicBool = AtomicBool::new(false);
 
+thread_local! {
+    static LINE_PREFIX: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
 pub fn set_machine_output(enabled: bool) {
     MACHINE_OUTPUT.store(enabled, Ordering::Relaxed);
 }
@@ -11,6 +16,32 @@ pub fn is_machine_output() -> bool {
     MACHINE_OUTPUT.load(Ordering::Relaxed)
 }
 
+/// Prefixes every line `outln!` prints from the current thread, so output of
+/// work running in parallel can be told apart.
+pub fn set_line_prefix(prefix: Option<String>) {
+    LINE_PREFIX.with(|current| *current.borrow_mut() = prefix);
+}
+
+pub fn line_prefix() -> Option<String> {
+    LINE_PREFIX.with(|current| current.borrow().clone())
+}
+
+/// Prints a line as `outln!` does: to stdout, or to stderr in machine output mode.
+pub fn print_line(line: std::fmt::Arguments) {
+    let prefix = line_prefix().unwrap_or_default();
+    if is_machine_output() {
+        eprintln!("{prefix}{line}");
+    } else {
+        println!("{prefix}{line}");
+    }
+}
+
+/// Prints a line of command output meant for stdout, such as a table or JSON,
+/// regardless of machine output mode.
+pub fn print_data(line: std::fmt::Arguments) {
+    println!("{line}");
+}
+
 pub fn configure_command_for_machine_output(command: &mut Command) -> &mut Command {
     if is_machine_output() {
         command.stdout(Stdio::null());
@@ -29,10 +60,6 @@ macro_rules! outln {
         }
     };
     ($($arg:tt)*) => {
-        if $crate::output::is_machine_output() {
-            eprintln!($($arg)*);
-        } else {
-            println!($($arg)*);
-        }
+        $crate::output::print_line(format_args!($($arg)*))
     };
 }
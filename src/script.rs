@@ -0,0 +1,230 @@
+use anyhow::{Context, Result};
+use indicatif::ProgressBar;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::Path;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use wait_timeout::ChildExt;
+
+use crate::config::ScriptInterpreter;
+use crate::interrupt;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How a streamed script ended.
+#[derive(Debug)]
+pub enum ScriptStatus {
+    Exited(ExitStatus),
+    TimedOut,
+    Interrupted,
+}
+
+/// The command that runs `script` with `interpreter`.
+pub fn script_command(interpreter: &ScriptInterpreter, script: &Path) -> Command {
+    let words = match interpreter {
+        ScriptInterpreter::Exec => return Command::new(script),
+        ScriptInterpreter::Command(words) => words,
+    };
+    let Some((program, args)) = words.split_first() else {
+        return Command::new(script);
+    };
+
+    let mut command = Command::new(program);
+    if args.iter().any(|arg| arg.contains("{script}")) {
+        let script = script.to_string_lossy();
+        command.args(args.iter().map(|arg| arg.replace("{script}", &script)));
+    } else {
+        command.args(args).arg(script);
+    }
+    command
+}
+
+/// Runs `command`, echoing its output line by line while it runs and appending
+/// both streams to `log_path`. Stdout lines go through `outln!`, so they stay
+/// off stdout in print-path mode. The script and every process it started are
+/// killed when `timeout` elapses or Ctrl-C is recorded.
+pub fn run_streaming(
+    mut command: Command,
+    log_path: &Path,
+    timeout: Option<Duration>,
+    pb: &ProgressBar,
+) -> Result<ScriptStatus> {
+    if let Some(parent) = log_path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let log = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log_path)
+        .with_context(|| format!("Failed to open {}", log_path.display()))?;
+    let log = Arc::new(Mutex::new(log));
+
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    // Run in its own process group so a timeout can kill whatever it spawned
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    let mut child = command.spawn().context("Failed to start script")?;
+
+    let readers = [
+        child
+            .stdout
+            .take()
+            .map(|stdout| forward_lines(stdout, false, &log, pb)),
+        child
+            .stderr
+            .take()
+            .map(|stderr| forward_lines(stderr, true, &log, pb)),
+    ];
+
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.wait_timeout(POLL_INTERVAL)? {
+            break ScriptStatus::Exited(status);
+        }
+        let timed_out = timeout.is_some_and(|timeout| started.elapsed() >= timeout);
+        if timed_out || interrupt::interrupted() {
+            kill_process_group(&mut child);
+            child.wait()?;
+            break if timed_out {
+                ScriptStatus::TimedOut
+            } else {
+                ScriptStatus::Interrupted
+            };
+        }
+    };
+
+    for reader in readers.into_iter().flatten() {
+        let _ = reader.join();
+    }
+    Ok(status)
+}
+
+fn forward_lines(
+    stream: impl Read + Send + 'static,
+    is_stderr: bool,
+    log: &Arc<Mutex<File>>,
+    pb: &ProgressBar,
+) -> JoinHandle<()> {
+    let log = Arc::clone(log);
+    let pb = pb.clone();
+    let prefix = crate::output::line_prefix();
+    thread::spawn(move || {
+        crate::output::set_line_prefix(prefix.clone());
+        let mut reader = BufReader::new(stream);
+        let mut buf = Vec::new();
+        while matches!(reader.read_until(b'\n', &mut buf), Ok(n) if n > 0) {
+            if let Ok(mut log) = log.lock() {
+                let _ = log.write_all(&buf);
+            }
+            let line = String::from_utf8_lossy(&buf);
+            let line = line.trim_end_matches(['\n', '\r']);
+            pb.suspend(|| {
+                if is_stderr {
+                    eprintln!("{}   {line}", prefix.as_deref().unwrap_or_default());
+                } else {
+                    crate::outln!("   {line}");
+                }
+            });
+            buf.clear();
+        }
+    })
+}
+
+fn kill_process_group(child: &mut Child) {
+    #[cfg(unix)]
+    // SAFETY: signals the process group created for the child in `run_streaming`
+    unsafe {
+        libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
+    }
+    #[cfg(not(unix))]
+    let _ = child.kill();
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn sh(script: &str) -> Command {
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(script);
+        command
+    }
+
+    fn args_of(command: &Command) -> Vec<String> {
+        std::iter::once(command.get_program())
+            .chain(command.get_args())
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn builds_command_for_interpreter() {
+        let script = Path::new("/repo/Makefile");
+        let words = |s: &str| s.split_whitespace().map(String::from).collect();
+
+        assert_eq!(
+            args_of(&script_command(&ScriptInterpreter::default(), script)),
+            ["bash", "/repo/Makefile"]
+        );
+        assert_eq!(
+            args_of(&script_command(&ScriptInterpreter::Exec, script)),
+            ["/repo/Makefile"]
+        );
+        assert_eq!(
+            args_of(&script_command(
+                &ScriptInterpreter::Command(words("make -f {script} setup")),
+                script
+            )),
+            ["make", "-f", "/repo/Makefile", "setup"]
+        );
+    }
+
+    #[test]
+    fn logs_both_streams_and_reports_exit_status() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let log_path = temp_dir.path().join("logs/setup.log");
+
+        let status = run_streaming(
+            sh("echo out; echo err >&2; exit 3"),
+            &log_path,
+            None,
+            &ProgressBar::hidden(),
+        )?;
+        assert!(matches!(status, ScriptStatus::Exited(s) if s.code() == Some(3)));
+
+        let log = fs::read_to_string(&log_path)?;
+        assert!(log.contains("out\n"));
+        assert!(log.contains("err\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn kills_script_and_children_on_timeout() -> Result<()> {
+        let _lock = interrupt::TEST_LOCK
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let temp_dir = TempDir::new()?;
+        let started = Instant::now();
+
+        let status = run_streaming(
+            sh("sleep 30 & sleep 30"),
+            &temp_dir.path().join("setup.log"),
+            Some(Duration::from_millis(200)),
+            &ProgressBar::hidden(),
+        )?;
+        assert!(matches!(status, ScriptStatus::TimedOut));
+        assert!(started.elapsed() < Duration::from_secs(10));
+        Ok(())
+    }
+}
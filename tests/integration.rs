@@ -32,6 +32,17 @@ fn test_cleanup_help_command() {
         .stdout(predicate::str::contains("Clean up worktrees"));
 }
 
+#[test]
+fn test_list_help_command() {
+    Command::cargo_bin("workbloom")
+        .unwrap()
+        .args(["list", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("List worktrees"))
+        .stdout(predicate::str::contains("--json"));
+}
+
 #[test]
 fn test_version_command() {
     Command::cargo_bin("workbloom")
@@ -223,3 +234,420 @@ files_to_copy = [".workbloom-setup.sh"]
     // Now let's verify the file exists (actual worktree setup would require more complex testing)
     assert!(repo_path.join(".workbloom-setup.sh").exists());
 }
+
+fn init_repo_with_origin(repo_path: &std::path::Path) {
+    use std::process::Command as StdCommand;
+
+    for args in [
+        vec!["init"],
+        vec!["config", "user.email", "test@example.com"],
+        vec!["config", "user.name", "Test User"],
+        vec!["commit", "--allow-empty", "-m", "Initial commit"],
+        vec!["branch", "-M", "main"],
+        vec!["remote", "add", "origin", "."],
+    ] {
+        let status = StdCommand::new("git")
+            .args(&args)
+            .current_dir(repo_path)
+            .status()
+            .expect("Failed to run git");
+        assert!(status.success(), "git {args:?} failed");
+    }
+}
+
+/// Stages every change in `repo_path` and commits it.
+fn commit_all(repo_path: &std::path::Path, message: &str) {
+    use std::process::Command as StdCommand;
+
+    for args in [vec!["add", "-A"], vec!["commit", "-q", "-m", message]] {
+        let status = StdCommand::new("git")
+            .args(&args)
+            .current_dir(repo_path)
+            .status()
+            .expect("Failed to run git");
+        assert!(status.success(), "git {args:?} failed");
+    }
+}
+
+/// Runs workbloom in `repo_path` without colors. Approvals go to a trust store
+/// inside the repository's `.git`, so tests never read or write the real one.
+fn run_workbloom(repo_path: &std::path::Path, args: &[&str]) -> std::process::Output {
+    Command::cargo_bin("workbloom")
+        .unwrap()
+        .args(args)
+        .current_dir(repo_path)
+        .env("NO_COLOR", "1")
+        .env("XDG_DATA_HOME", repo_path.join(".git/test-data"))
+        .output()
+        .expect("Failed to run workbloom")
+}
+
+#[test]
+fn test_setup_reuses_existing_worktree() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    let repo_path = temp_dir.path();
+    init_repo_with_origin(repo_path);
+
+    let run_setup = || run_workbloom(repo_path, &["setup", "test-branch"]);
+
+    let first = run_setup();
+    assert!(first.status.success());
+
+    let second = run_setup();
+    assert!(second.status.success());
+    assert_eq!(first.stdout, second.stdout);
+    let stderr = String::from_utf8_lossy(&second.stderr);
+    assert!(stderr.contains("already exists"));
+    assert!(!stderr.contains("Creating git worktree"));
+}
+
+#[test]
+fn test_setup_rolls_back_on_failure() {
+    use std::fs;
+    use std::process::Command as StdCommand;
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    let repo_path = temp_dir.path();
+    init_repo_with_origin(repo_path);
+
+    // `bar` is a file in the commit but a directory in the main checkout, so
+    // copying it into the new worktree fails after the worktree was created
+    fs::write(repo_path.join("bar"), "tracked").unwrap();
+    commit_all(repo_path, "Add bar");
+    fs::remove_file(repo_path.join("bar")).unwrap();
+    fs::create_dir(repo_path.join("bar")).unwrap();
+    fs::write(repo_path.join("bar/file"), "local").unwrap();
+    fs::write(repo_path.join(".workbloom"), "bar\n").unwrap();
+
+    let output = run_workbloom(repo_path, &["setup", "broken"]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("rolling back"));
+    assert!(!repo_path.join("worktree-broken").exists());
+    let branches = StdCommand::new("git")
+        .args(["branch", "--list", "broken"])
+        .current_dir(repo_path)
+        .output()
+        .unwrap();
+    assert!(branches.stdout.is_empty());
+}
+
+#[test]
+fn test_setup_skips_untrusted_script_until_allowed() {
+    use std::fs;
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    let repo_path = temp_dir.path();
+    init_repo_with_origin(repo_path);
+
+    fs::write(
+        repo_path.join(".workbloom-setup.sh"),
+        "#!/bin/bash\ntouch .setup-ran\n",
+    )
+    .unwrap();
+    commit_all(repo_path, "Add setup script");
+
+    let output = run_workbloom(repo_path, &["setup", "untrusted"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("workbloom allow"));
+    assert!(!repo_path.join("worktree-untrusted/.setup-ran").exists());
+
+    assert!(run_workbloom(repo_path, &["allow"]).status.success());
+
+    let output = run_workbloom(repo_path, &["setup", "trusted"]);
+    assert!(output.status.success());
+    assert!(repo_path.join("worktree-trusted/.setup-ran").exists());
+}
+
+#[test]
+fn test_setup_script_output_is_logged_and_strict_mode_fails_setup() {
+    use std::fs;
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    let repo_path = temp_dir.path();
+    init_repo_with_origin(repo_path);
+
+    fs::write(
+        repo_path.join(".workbloom-setup.sh"),
+        "#!/bin/bash\necho installing\nexit 1\n",
+    )
+    .unwrap();
+    commit_all(repo_path, "Add setup script");
+    assert!(run_workbloom(repo_path, &["allow"]).status.success());
+
+    let output = run_workbloom(repo_path, &["setup", "lenient"]);
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("installing"));
+    assert!(stderr.contains("exited with status 1"));
+    let log = fs::read_to_string(repo_path.join(".git/workbloom/logs/setup/lenient.log")).unwrap();
+    assert_eq!(log, "installing\n");
+
+    let output = run_workbloom(repo_path, &["setup", "strict", "--strict"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("rolling back"));
+    assert!(!repo_path.join("worktree-strict").exists());
+}
+
+#[test]
+fn test_setup_batch_reports_each_branch() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    let repo_path = temp_dir.path();
+    init_repo_with_origin(repo_path);
+
+    let output = run_workbloom(repo_path, &["setup", "one", "bad..name", "two"]);
+
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let paths: Vec<&str> = stdout.lines().collect();
+    assert_eq!(
+        paths,
+        [
+            repo_path.join("worktree-one").to_string_lossy(),
+            repo_path.join("worktree-two").to_string_lossy(),
+        ]
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("bad..name"));
+    assert!(stderr.contains("1 of 3 branches failed"));
+
+    let output = run_workbloom(repo_path, &["setup", "--json", "one", "three"]);
+
+    assert!(output.status.success());
+    let entries: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(entries[0]["status"], "reused");
+    assert_eq!(entries[1]["branch"], "three");
+    assert_eq!(entries[1]["status"], "created");
+}
+
+#[test]
+fn test_setup_ignores_entries_outside_the_repository() {
+    use std::fs;
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    let repo_path = temp_dir.path().join("repo");
+    fs::create_dir(&repo_path).unwrap();
+    init_repo_with_origin(&repo_path);
+    fs::write(temp_dir.path().join("secret"), "outside").unwrap();
+    fs::write(repo_path.join(".workbloom"), "# copy\n../secret\n").unwrap();
+
+    let output = run_workbloom(&repo_path, &["setup", "contained"]);
+
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("ignoring .workbloom line 2 (../secret)"));
+    assert!(!repo_path.join("secret").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_setup_leaves_main_checkout_script_mode_alone() {
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    let repo_path = temp_dir.path();
+    init_repo_with_origin(repo_path);
+
+    let script = repo_path.join(".workbloom-setup.sh");
+    fs::write(&script, "#!/bin/sh\ntouch .setup-ran\n").unwrap();
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o644)).unwrap();
+    fs::write(
+        repo_path.join(".workbloom"),
+        "setup_script_interpreter = exec\nsetup_script_source = main\n",
+    )
+    .unwrap();
+    assert!(run_workbloom(repo_path, &["allow"]).status.success());
+
+    let output = run_workbloom(repo_path, &["setup", "exec-main"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("not executable"));
+    let mode = fs::metadata(&script).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o644);
+}
+
+#[test]
+fn test_setup_fetch_uses_tag_start_point_as_is() {
+    use std::process::Command as StdCommand;
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    let repo_path = temp_dir.path();
+    init_repo_with_origin(repo_path);
+
+    let git = |args: &[&str]| {
+        let output = StdCommand::new("git")
+            .args(args)
+            .current_dir(repo_path)
+            .output()
+            .expect("Failed to run git");
+        assert!(output.status.success(), "git {args:?} failed");
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    };
+    git(&["tag", "v1.2.0"]);
+    git(&["commit", "-q", "--allow-empty", "-m", "After release"]);
+
+    let output = run_workbloom(
+        repo_path,
+        &["setup", "hotfix", "--from", "v1.2.0", "--fetch"],
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        git(&["rev-parse", "hotfix"]),
+        git(&["rev-parse", "v1.2.0^{commit}"])
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_setup_shows_git_hook_output() {
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    let repo_path = temp_dir.path();
+    init_repo_with_origin(repo_path);
+
+    let hook = repo_path.join(".git/hooks/post-checkout");
+    fs::write(&hook, "#!/bin/sh\necho post-checkout ran >&2\n").unwrap();
+    fs::set_permissions(&hook, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let output = run_workbloom(repo_path, &["setup", "hooked"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("post-checkout ran"));
+}
+
+#[test]
+fn test_setup_background_cleanup_appends_to_log() {
+    use std::fs;
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    let repo_path = temp_dir.path();
+    init_repo_with_origin(repo_path);
+
+    fs::write(repo_path.join(".workbloom"), "setup_cleanup = background\n").unwrap();
+    let log_path = repo_path.join(".git/workbloom/logs/cleanup.log");
+    fs::create_dir_all(log_path.parent().unwrap()).unwrap();
+    fs::write(&log_path, "earlier cleanup\n").unwrap();
+
+    let output = run_workbloom(repo_path, &["setup", "deferred"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("worktree-deferred"));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("in the background"));
+
+    // A branch named after the log keeps its setup log elsewhere
+    assert!(run_workbloom(repo_path, &["setup", "cleanup"])
+        .status
+        .success());
+    assert!(fs::read_to_string(&log_path)
+        .unwrap()
+        .starts_with("earlier cleanup\n"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_setup_prints_outside_worktree_path_from_symlinked_cwd() {
+    use std::fs;
+    use std::os::unix::fs::symlink;
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    let root = fs::canonicalize(temp_dir.path()).unwrap();
+    let repo_path = root.join("deep/a/b/repo");
+    fs::create_dir_all(&repo_path).unwrap();
+    init_repo_with_origin(&repo_path);
+    fs::write(
+        repo_path.join(".workbloom"),
+        format!("worktree_dir = {}/wt/{{repo}}/{{branch}}\n", root.display()),
+    )
+    .unwrap();
+    let link = root.join("r");
+    symlink(&repo_path, &link).unwrap();
+
+    let output = Command::cargo_bin("workbloom")
+        .unwrap()
+        .args(["setup", "x"])
+        .current_dir(&link)
+        .env("PWD", &link)
+        .env("NO_COLOR", "1")
+        .env("XDG_DATA_HOME", repo_path.join(".git/test-data"))
+        .output()
+        .expect("Failed to run workbloom");
+
+    assert!(output.status.success());
+    let printed = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    assert_eq!(printed, root.join("wt/repo/x").display().to_string());
+}
+
+#[test]
+fn test_setup_warns_when_from_is_ignored_for_remote_branch() {
+    use std::process::Command as StdCommand;
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    let repo_path = temp_dir.path();
+    init_repo_with_origin(repo_path);
+
+    // With origin pointing at the repository itself, a branch that only exists
+    // on origin needs a separate remote
+    let remote = TempDir::new().unwrap();
+    for args in [
+        vec![
+            "clone",
+            "-q",
+            "--bare",
+            ".",
+            remote.path().to_str().unwrap(),
+        ],
+        vec![
+            "remote",
+            "set-url",
+            "origin",
+            remote.path().to_str().unwrap(),
+        ],
+        vec!["push", "-q", "origin", "main:remote-only"],
+    ] {
+        let status = StdCommand::new("git")
+            .args(&args)
+            .current_dir(repo_path)
+            .status()
+            .expect("Failed to run git");
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    let output = run_workbloom(repo_path, &["setup", "remote-only", "--from", "main"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Ignoring --from main"));
+}
+
+#[test]
+fn test_list_json_keeps_warnings_off_stdout() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    let repo_path = temp_dir.path();
+    init_repo_with_origin(repo_path);
+    std::fs::write(repo_path.join(".workbloom"), "protect_hours = soon\n").unwrap();
+
+    let output = run_workbloom(repo_path, &["list", "--json"]);
+    assert!(output.status.success());
+    let entries: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(entries.is_array());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("ignoring .workbloom line 1"));
+}